@@ -1,15 +1,10 @@
-use anchor_lang::prelude::{constant, Pubkey};
-use solana_program::pubkey;
+use anchor_lang::prelude::constant;
 
-#[constant]
-pub const AUTHORIZED_PUBLIC_KEY: Pubkey = pubkey!("55kBY9yxqSC42boV8PywT2gqGzgLi5MPAtifNRgPNezF");
 #[constant]
 pub const MAX_INT: u128 = u128::pow(2, 64) - 1;
 #[constant]
 pub const VOTING_END: i64 = 72 * 60 * 60;
 #[constant]
-pub const TRUSTED_NAME_ROUTERS: &[Pubkey] = &[AUTHORIZED_PUBLIC_KEY];
-#[constant]
 pub const VESTING_NUMBER: u64 = 4;
 #[constant]
 pub const TOKEN_VEST_AMOUNT: u64 = 2500;
@@ -17,3 +12,33 @@ pub const TOKEN_VEST_AMOUNT: u64 = 2500;
 pub const RELEASE_TIME: u64 = u64::pow(10, 6);
 #[constant]
 pub const DEFAULT_MINT_DECIMALS: u8 = 1;
+#[constant]
+pub const BASIS_POINTS: u64 = 10000;
+#[constant]
+pub const MAX_TRADE_FEE_BPS: u16 = 1000;
+#[constant]
+pub const PROTOCOL_FEE_SHARE_BPS: u16 = 2000;
+#[constant]
+pub const MAX_VESTING_SCHEDULES: usize = 10;
+#[constant]
+pub const MAX_TRUSTED_NAME_ROUTERS: usize = 10;
+#[constant]
+pub const MAX_BOUNTY_MINTS: usize = 4;
+#[constant]
+pub const MAX_STAKE_LOCK_DURATION: i64 = 30 * 24 * 60 * 60;
+#[constant]
+pub const MAX_LOCK_BONUS_BPS: u64 = 10000;
+#[constant]
+pub const LATE_STAKE_VOTING_WEIGHT_BPS: u64 = 2500;
+#[constant]
+pub const MAX_REWARD_SHARES: usize = 8;
+#[constant]
+pub const MAX_ISSUE_LABELS: usize = 5;
+#[constant]
+pub const MAX_LABEL_LENGTH: usize = 32;
+#[constant]
+pub const MAX_REWARD_CUTS_BPS: u16 = 5000;
+#[constant]
+pub const CONVICTION_PERIOD: i64 = 7 * 24 * 60 * 60;
+#[constant]
+pub const MIN_CONVICTION_WEIGHT_BPS: u64 = 2500;
@@ -0,0 +1,97 @@
+use crate::state::{CurveConfig, CurveType};
+
+//fixed point scale used by the exponential curve
+pub const EXP_PRECISION: u128 = 1_000_000_000_000;
+const E_FIXED: u128 = 2_718_281_828_459;
+const EXP_TAYLOR_TERMS: u128 = 12;
+
+//curve functions, all supplies and amounts are in whole tokens
+pub fn validate_curve(curve: &CurveConfig) -> bool {
+    match curve.curve_type {
+        CurveType::Linear | CurveType::Quadratic => curve.slope > 0 || curve.base_price > 0,
+        CurveType::Exponential => curve.slope > 0 && curve.base_price > 0,
+    }
+}
+
+//usdc that has to be held in reserve once `supply` tokens have been bought off the curve
+pub fn reserve_at(curve: &CurveConfig, supply: u64) -> Option<u128> {
+    //a degenerate curve would price every token at zero
+    if !validate_curve(curve) {
+        return None;
+    }
+    let supply = supply as u128;
+    let slope = curve.slope as u128;
+    let base_price = curve.base_price as u128;
+    match curve.curve_type {
+        // price = base_price + slope * supply
+        CurveType::Linear => {
+            let area = slope.checked_mul(supply.checked_pow(2)?)? / 2;
+            base_price.checked_mul(supply)?.checked_add(area)
+        }
+        // price = base_price + slope * supply^2
+        CurveType::Quadratic => {
+            let area = slope.checked_mul(supply.checked_pow(3)?)? / 3;
+            base_price.checked_mul(supply)?.checked_add(area)
+        }
+        // price = base_price * e^(supply / slope)
+        CurveType::Exponential => {
+            let growth = exp_fixed(supply, slope)?.checked_sub(EXP_PRECISION)?;
+            Some(base_price.checked_mul(slope)?.checked_mul(growth)? / EXP_PRECISION)
+        }
+    }
+}
+
+pub fn calculate_buy_amount(
+    curve: &CurveConfig,
+    token_supply: u64,
+    token_amount: u64,
+) -> Option<u128> {
+    let new_supply = token_supply.checked_add(token_amount)?;
+    reserve_at(curve, new_supply)?.checked_sub(reserve_at(curve, token_supply)?)
+}
+
+pub fn calculate_sell_amount(
+    curve: &CurveConfig,
+    token_supply: u64,
+    token_amount: u64,
+) -> Option<u128> {
+    let new_supply = token_supply.checked_sub(token_amount)?;
+    reserve_at(curve, token_supply)?.checked_sub(reserve_at(curve, new_supply)?)
+}
+
+//compares a reserve balance with what the curve requires for `supply`, returns (surplus, deficit)
+pub fn check_reserve(
+    curve: &CurveConfig,
+    supply: u64,
+    reserve_balance: u64,
+) -> Option<(u128, u128)> {
+    let required_reserve = reserve_at(curve, supply)?;
+    let reserve_balance = reserve_balance as u128;
+    if reserve_balance >= required_reserve {
+        Some((reserve_balance - required_reserve, 0))
+    } else {
+        Some((0, required_reserve - reserve_balance))
+    }
+}
+
+//e^(numerator / denominator) scaled by EXP_PRECISION
+fn exp_fixed(numerator: u128, denominator: u128) -> Option<u128> {
+    let whole = numerator.checked_div(denominator)?;
+    let fraction = (numerator % denominator).checked_mul(EXP_PRECISION)? / denominator;
+
+    let mut result = EXP_PRECISION;
+    for _ in 0..whole {
+        result = result.checked_mul(E_FIXED)? / EXP_PRECISION;
+    }
+
+    let mut term = EXP_PRECISION;
+    let mut fraction_exp = EXP_PRECISION;
+    for i in 1..=EXP_TAYLOR_TERMS {
+        term = term.checked_mul(fraction)? / EXP_PRECISION / i;
+        fraction_exp += term;
+    }
+
+    result
+        .checked_mul(fraction_exp)
+        .map(|value| value / EXP_PRECISION)
+}
@@ -141,4 +141,168 @@ pub enum DefiOSError {
     //6034
     #[msg("Issue cant be unstaked if never staked on")]
     CantUnnstakeBeforeStaking,
+
+    //6035
+    #[msg("Invalid bonding curve configuration")]
+    InvalidCurveConfig,
+
+    //6036
+    #[msg("Trade price moved beyond the allowed slippage")]
+    SlippageExceeded,
+
+    //6037
+    #[msg("Trade fee exceeds the allowed maximum")]
+    TradeFeeTooHigh,
+
+    //6038
+    #[msg("Invalid vesting plan")]
+    InvalidVestingPlan,
+
+    //6039
+    #[msg("No unvested tokens left to revoke")]
+    NoUnvestedTokens,
+
+    //6040
+    #[msg("Too many trusted name routers")]
+    TooManyTrustedNameRouters,
+
+    //6041
+    #[msg("Voting period has to be positive")]
+    InvalidVotingPeriod,
+
+    //6042
+    #[msg("Name router is not trusted by the program config")]
+    UntrustedNameRouter,
+
+    //6043
+    #[msg("Mint is not the configured quote mint")]
+    InvalidQuoteMint,
+
+    //6044
+    #[msg("Communal reserve cannot cover this payout")]
+    InsufficientReserve,
+
+    //6045
+    #[msg("Unstake amount has to be between zero and the staked balance")]
+    InvalidUnstakeAmount,
+
+    //6046
+    #[msg("Mint is not accepted as a bounty on this issue")]
+    MintNotAllowedOnIssue,
+
+    //6047
+    #[msg("Issue already accepts the maximum number of bounty mints")]
+    TooManyBountyMints,
+
+    //6048
+    #[msg("Mint is already accepted on this issue")]
+    BountyMintAlreadyAdded,
+
+    //6049
+    #[msg("Voting weight can not exceed 100%")]
+    InvalidVotingWeight,
+
+    //6050
+    #[msg("Accounts for every bounty pool on the issue are required")]
+    MissingBountyAccounts,
+
+    //6051
+    #[msg("Lock-up duration is longer than allowed")]
+    InvalidLockDuration,
+
+    //6052
+    #[msg("Stake is still locked")]
+    StakeLocked,
+
+    //6053
+    #[msg("Reward shares have to be unique, non zero and add up to 100%")]
+    InvalidRewardShares,
+
+    //6054
+    #[msg("No share of this issue's reward was accepted for this user")]
+    NoRewardShare,
+
+    //6055
+    #[msg("Reward share has already been claimed")]
+    RewardAlreadyClaimed,
+
+    //6056
+    #[msg("Issue deadline has to be in the future")]
+    InvalidIssueDeadline,
+
+    //6057
+    #[msg("Issue has no deadline or it has not passed yet")]
+    IssueDeadlineNotReached,
+
+    //6058
+    #[msg("Issue labels have to be unique, non empty and within the allowed count and length")]
+    InvalidIssueLabels,
+
+    //6059
+    #[msg("Reward cuts exceed the allowed maximum")]
+    InvalidRewardCuts,
+
+    //6060
+    #[msg("Votes can only be delegated to another verified user")]
+    InvalidDelegate,
+
+    //6061
+    #[msg("Voting power of this stake is delegated")]
+    VoteDelegated,
+
+    //6062
+    #[msg("Stake is not delegated to this voter")]
+    NotADelegate,
+
+    //6063
+    #[msg("Stake has already voted on a pull request")]
+    AlreadyVoted,
+
+    //6064
+    #[msg("Stake has not voted on a pull request")]
+    NoVoteCast,
+
+    //6065
+    #[msg("Vote can only be moved to another pull request of the same issue")]
+    InvalidVoteChange,
+
+    //6066
+    #[msg("Quorum and approval threshold have to be valid basis points")]
+    InvalidGovernanceConfig,
+
+    //6067
+    #[msg("Not enough voting power took part in the vote")]
+    QuorumNotReached,
+
+    //6068
+    #[msg("Pull request can only be withdrawn before it receives votes")]
+    PullRequestHasVotes,
+
+    //6069
+    #[msg("Issue has to be closed first")]
+    IssueNotClosed,
+
+    //6070
+    #[msg("Pull request has been accepted")]
+    PullRequestAlreadyAccepted,
+
+    //6071
+    #[msg("Repository predates configurable curves and has to be migrated first")]
+    RepositoryNotMigrated,
+
+    //6072
+    #[msg("Repository has already been migrated")]
+    RepositoryAlreadyMigrated,
+
+    //6073
+    #[msg("Quadratic voting needs stakers to be verified users")]
+    VerifiedUserRequired,
+
+    //6074
+    #[msg("Token amount has to be a whole number of tokens")]
+    FractionalTokenAmount,
+
+    //6075
+    #[msg("Issue token pool account is required to claim rewards on a staked issue")]
+    MissingIssueTokenPool,
 }
@@ -1,4 +1,7 @@
-use crate::state::{ObjectiveDeliverable, RoadmapOutlook};
+use crate::state::{
+    CurveConfig, GovernanceConfig, IssueDifficulty, IssuePriority, ObjectiveDeliverable,
+    RoadmapOutlook, VotingMode,
+};
 use anchor_lang::prelude::*;
 #[event]
 pub struct PullRequestSent {
@@ -8,6 +11,28 @@ pub struct PullRequestSent {
     pub pull_request: Pubkey,
 }
 
+#[event]
+pub struct PullRequestUpdated {
+    pub sent_by: Pubkey,
+    pub metadata_uri: String,
+    pub issue: Pubkey,
+    pub pull_request: Pubkey,
+}
+
+#[event]
+pub struct PullRequestWithdrawn {
+    pub sent_by: Pubkey,
+    pub issue: Pubkey,
+    pub pull_request: Pubkey,
+}
+
+#[event]
+pub struct PullRequestClosed {
+    pub sent_by: Pubkey,
+    pub issue: Pubkey,
+    pub pull_request: Pubkey,
+}
+
 #[event]
 pub struct AddCommitToPR {
     pub commit: Vec<Pubkey>,
@@ -68,6 +93,36 @@ pub struct IssueCreated {
     pub issue_account: Pubkey,
     pub repository_account: Pubkey,
     pub uri: String,
+    pub deadline: Option<i64>,
+    pub labels: Vec<String>,
+    pub priority: IssuePriority,
+    pub difficulty: IssueDifficulty,
+}
+
+#[event]
+pub struct IssueUpdated {
+    pub issue: Pubkey,
+    pub repository: Pubkey,
+    pub labels: Vec<String>,
+    pub priority: IssuePriority,
+    pub difficulty: IssueDifficulty,
+}
+
+#[event]
+pub struct IssueCancelled {
+    pub issue: Pubkey,
+    pub repository: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub cancelled_at: i64,
+    pub total_stake_amount: u64,
+}
+
+#[event]
+pub struct IssueExpired {
+    pub issue: Pubkey,
+    pub repository: Pubkey,
+    pub expired_at: i64,
+    pub total_stake_amount: u64,
 }
 
 #[event]
@@ -83,6 +138,9 @@ pub struct RepositoryCreated {
     pub token_metadata_uri: Option<String>,
     pub vesting_account: Option<Pubkey>,
     pub token_imported: bool,
+    pub curve_config: CurveConfig,
+    pub trade_fee_bps: u16,
+    pub vesting_allocation: u64,
 }
 
 #[event]
@@ -95,6 +153,7 @@ pub struct IssueStaked {
     pub issue_contribution_link: String,
     pub staked_at: i64,
     pub pr_voting_power: u64,
+    pub locked_until: i64,
 }
 
 #[event]
@@ -103,6 +162,7 @@ pub struct IssueUnstaked {
     pub issue_staker_token_account: Pubkey,
     pub issue_account: Pubkey,
     pub unstaked_amount: u64,
+    pub remaining_staked_amount: u64,
     pub rewards_mint: Pubkey,
     pub issue_contribution_link: String,
 }
@@ -121,13 +181,103 @@ pub struct PRVoted {
     pub pull_request: Pubkey,
     pub vote_amount: u64,
     pub voter: Pubkey,
+    pub delegated_amount: u64,
+}
+
+#[event]
+pub struct VoteDelegated {
+    pub issue: Pubkey,
+    pub issue_staker: Pubkey,
+    pub delegate: Pubkey,
+}
+
+#[event]
+pub struct VoteDelegationRevoked {
+    pub issue: Pubkey,
+    pub issue_staker: Pubkey,
+    pub delegate: Pubkey,
+}
+
+#[event]
+pub struct VoteChanged {
+    pub issue: Pubkey,
+    pub issue_staker: Pubkey,
+    pub voter: Pubkey,
+    pub from_pull_request: Pubkey,
+    pub to_pull_request: Pubkey,
+    pub vote_amount: u64,
+}
+
+#[event]
+pub struct VoteWithdrawn {
+    pub issue: Pubkey,
+    pub issue_staker: Pubkey,
+    pub voter: Pubkey,
+    pub pull_request: Pubkey,
+    pub vote_amount: u64,
 }
 
 #[event]
 pub struct RewardClaimed {
     pub reward_claimmee: Pubkey,
     pub reward_amount: u64,
-    pub pull_request: Pubkey,
+    pub pull_request: Option<Pubkey>,
+    pub share_bps: u16,
+    pub maintainer: Pubkey,
+    pub maintainer_amount: u64,
+    pub issue_reporter: Pubkey,
+    pub issue_reporter_amount: u64,
+}
+
+#[event]
+pub struct VotingModeUpdated {
+    pub repository: Pubkey,
+    pub voting_mode: VotingMode,
+}
+
+#[event]
+pub struct GovernanceConfigUpdated {
+    pub repository: Pubkey,
+    pub governance_config: GovernanceConfig,
+}
+
+#[event]
+pub struct RepositoryMigrated {
+    pub repository: Pubkey,
+    pub curve_config: CurveConfig,
+    pub governance_config: GovernanceConfig,
+}
+
+#[event]
+pub struct RewardCutsUpdated {
+    pub repository: Pubkey,
+    pub maintainer_cut_bps: u16,
+    pub issue_reporter_cut_bps: u16,
+}
+
+#[event]
+pub struct RewardSplitAccepted {
+    pub issue: Pubkey,
+    pub repository: Pubkey,
+    pub recipients: Vec<Pubkey>,
+    pub shares_bps: Vec<u16>,
+}
+
+#[event]
+pub struct BountyMintAdded {
+    pub issue: Pubkey,
+    pub bounty_mint: Pubkey,
+    pub voting_weight_bps: u16,
+}
+
+#[event]
+pub struct BountyRewardClaimed {
+    pub reward_claimmee: Pubkey,
+    pub bounty_mint: Pubkey,
+    pub reward_amount: u64,
+    pub pull_request: Option<Pubkey>,
+    pub maintainer_amount: u64,
+    pub issue_reporter_amount: u64,
 }
 
 #[event]
@@ -136,6 +286,15 @@ pub struct RepoTokenChanged {
     pub new_token: Pubkey,
 }
 
+#[event]
+pub struct RepoTokenMetadataUpdated {
+    pub repository: Pubkey,
+    pub rewards_mint: Pubkey,
+    pub token_name: String,
+    pub token_symbol: String,
+    pub token_metadata_uri: String,
+}
+
 #[event]
 pub struct GrantProvided {
     pub grantee: Pubkey,
@@ -178,3 +337,84 @@ pub struct ObjectiveAccepted {
     pub objective: Pubkey,
     pub objective_proposal: Pubkey,
 }
+
+#[event]
+pub struct TokensSwapped {
+    pub swapper: Pubkey,
+    pub repository_in: Pubkey,
+    pub repository_out: Pubkey,
+    pub number_of_tokens_in: u64,
+    pub number_of_tokens_out: u64,
+    pub usdc_amount: u64,
+}
+
+#[event]
+pub struct TokensBought {
+    pub buyer: Pubkey,
+    pub repository: Pubkey,
+    pub rewards_mint: Pubkey,
+    pub number_of_tokens: u64,
+    pub usdc_amount: u64,
+}
+
+#[event]
+pub struct TokensSold {
+    pub seller: Pubkey,
+    pub repository: Pubkey,
+    pub rewards_mint: Pubkey,
+    pub number_of_tokens: u64,
+    pub usdc_amount: u64,
+}
+
+#[event]
+pub struct TradeFeeCollected {
+    pub repository: Pubkey,
+    pub trader: Pubkey,
+    pub rewards_mint: Pubkey,
+    pub protocol_fee: u64,
+    pub repository_fee: u64,
+}
+
+#[event]
+pub struct TreasuryFeesWithdrawn {
+    pub treasury: Pubkey,
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct VestingBeneficiaryChanged {
+    pub repository: Pubkey,
+    pub vesting_account: Pubkey,
+    pub old_destination: Pubkey,
+    pub new_destination: Pubkey,
+    pub new_beneficiary: Pubkey,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub repository: Pubkey,
+    pub vesting_account: Pubkey,
+    pub revoked_amount: u64,
+}
+
+#[event]
+pub struct ProgramConfigUpdated {
+    pub admin: Pubkey,
+    pub quote_mint: Pubkey,
+    pub trusted_name_routers: Vec<Pubkey>,
+    pub voting_end: i64,
+    pub default_governance_config: GovernanceConfig,
+}
+
+#[event]
+pub struct ReserveAudited {
+    pub repository: Pubkey,
+    pub rewards_mint: Pubkey,
+    pub circulating_supply: u64,
+    pub required_reserve: u64,
+    pub reserve_balance: u64,
+    pub surplus: u64,
+    pub deficit: u64,
+}
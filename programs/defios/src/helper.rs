@@ -1,14 +1,20 @@
-use anchor_lang::prelude::Pubkey;
+use crate::constants::{
+    BASIS_POINTS, CONVICTION_PERIOD, LATE_STAKE_VOTING_WEIGHT_BPS, MAX_ISSUE_LABELS,
+    MAX_LABEL_LENGTH, MAX_LOCK_BONUS_BPS, MAX_REWARD_SHARES, MAX_STAKE_LOCK_DURATION,
+    MAX_VESTING_SCHEDULES, MIN_CONVICTION_WEIGHT_BPS, PROTOCOL_FEE_SHARE_BPS,
+};
+use crate::curve::{calculate_buy_amount, calculate_sell_amount};
+use crate::state::{
+    CurveConfig, GovernanceConfig, IssueStaker, Repository, RewardSplit, Schedule, TradeQuote,
+    VestingMode, VestingPlan, VotingMode,
+};
+use anchor_lang::prelude::{AccountInfo, Pubkey};
 use anchor_spl::metadata::mpl_token_metadata::ID;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+};
 //helper functions
-#[inline(always)]
-pub fn calculate_buy_amount(token_supply: u64, token_amount: u64) -> u128 {
-    let new_token_amount = token_amount as u128;
-    let new_token_supply = token_supply as u128;
-    let value = u128::pow(new_token_amount, 2) + (2 * new_token_supply * new_token_amount);
-    value
-}
-
 #[inline(always)]
 pub fn check_equal_buy(lamports: u128, ideal_lamports: u128) -> bool {
     if lamports >= ideal_lamports && lamports - ideal_lamports <= 2 {
@@ -17,49 +23,500 @@ pub fn check_equal_buy(lamports: u128, ideal_lamports: u128) -> bool {
     return false;
 }
 
+//converts raw mint supply into whole tokens sold off the curve, excluding the vested allocation
+pub fn get_modified_supply(
+    token_supply: u64,
+    vesting_allocation: u64,
+    decimals: u8,
+) -> Option<u64> {
+    let unit = u64::checked_pow(10, decimals.into())?;
+    Some(token_supply.checked_sub(vesting_allocation)? / unit)
+}
+
+#[inline(always)]
+pub fn get_modified_tokens(token_amount: u64, decimals: u8) -> Option<u64> {
+    Some(token_amount / u64::checked_pow(10, decimals.into())?)
+}
+
+//the curve prices whole tokens, anything below one would be floored away for free
 #[inline(always)]
-pub fn calculate_sell_amount(token_supply: u64, token_amount: u64) -> u128 {
-    let new_token_amount = token_amount as u128;
-    let new_token_supply = token_supply as u128;
-    let first_value = 2 * new_token_supply * new_token_amount;
-    let second_value = u128::pow(new_token_amount, 2);
-    if second_value > first_value {
-        return 0;
+pub fn is_whole_token_amount(token_amount: u64, decimals: u8) -> Option<bool> {
+    Some(token_amount % u64::checked_pow(10, decimals.into())? == 0)
+}
+
+//rescales a raw bounty mint amount to the issue token's decimals
+pub fn normalize_bounty_amount(
+    amount: u64,
+    mint_decimals: u8,
+    issue_token_decimals: u8,
+) -> Option<u64> {
+    if issue_token_decimals >= mint_decimals {
+        amount.checked_mul(u64::checked_pow(
+            10,
+            (issue_token_decimals - mint_decimals).into(),
+        )?)
     } else {
-        return first_value - second_value;
+        Some(amount / u64::checked_pow(10, (mint_decimals - issue_token_decimals).into())?)
     }
 }
 
-//main check code functions
-pub fn verify_calc_buy(token_supply: u64, number_of_lamports: u64, token_amount: u64) -> bool {
-    let value = calculate_buy_amount(token_supply, token_amount);
-    check_equal_buy(number_of_lamports as u128, value)
+//voting power a stake grants: scaled by the weight of its mint, boosted by the lock-up
+//chosen at stake time and cut down once a pull request is already up for vote
+pub fn get_stake_voting_power(
+    amount: u64,
+    voting_weight_bps: u16,
+    lock_duration: i64,
+    late_stake: bool,
+) -> Option<u64> {
+    let lock_duration = lock_duration.clamp(0, MAX_STAKE_LOCK_DURATION) as u128;
+    let lock_bonus_bps = (MAX_LOCK_BONUS_BPS as u128)
+        .checked_mul(lock_duration)?
+        .checked_div(MAX_STAKE_LOCK_DURATION as u128)?;
+    let mut voting_power = (amount as u128)
+        .checked_mul(voting_weight_bps as u128)?
+        .checked_mul((BASIS_POINTS as u128).checked_add(lock_bonus_bps)?)?
+        .checked_div((BASIS_POINTS as u128).checked_pow(2)?)?;
+    if late_stake {
+        voting_power = voting_power
+            .checked_mul(LATE_STAKE_VOTING_WEIGHT_BPS as u128)?
+            .checked_div(BASIS_POINTS as u128)?;
+    }
+    u64::try_from(voting_power).ok()
 }
 
-pub fn verify_calc_sell(token_supply: u64, number_of_lamports: u64, token_amount: u64) -> bool {
-    let value = calculate_sell_amount(token_supply, token_amount);
-    if (number_of_lamports as u128) == value {
-        return true;
+//voting power a staker carries on the pull request it voted for, across every mint it staked
+pub fn get_staker_voting_power(issue_staker: &IssueStaker) -> Option<u64> {
+    issue_staker
+        .bounty_stakes
+        .iter()
+        .try_fold(issue_staker.stake_voting_power, |total, bounty_stake| {
+            total.checked_add(bounty_stake.voting_power)
+        })
+}
+
+//approval has to be strictly above the threshold, so a full threshold could never pass
+pub fn validate_governance_config(governance_config: &GovernanceConfig) -> bool {
+    governance_config.quorum_bps as u64 <= BASIS_POINTS
+        && governance_config.approval_threshold_bps > 0
+        && (governance_config.approval_threshold_bps as u64) < BASIS_POINTS
+}
+
+//weight a staker's voting power adds to an issue's tally under the repository's voting mode
+pub fn get_mode_voting_power(voting_mode: VotingMode, voting_power: u64) -> u64 {
+    match voting_mode {
+        VotingMode::Quadratic => integer_sqrt(voting_power),
+        VotingMode::Linear | VotingMode::Conviction => voting_power,
+    }
+}
+
+//weight of a vote cast now, under conviction it grows with the time the stake has been committed
+pub fn get_cast_voting_power(
+    voting_mode: VotingMode,
+    voting_power: u64,
+    staked_at: i64,
+    current_time: i64,
+) -> Option<u64> {
+    let voting_power = get_mode_voting_power(voting_mode, voting_power);
+    if voting_mode != VotingMode::Conviction {
+        return Some(voting_power);
+    }
+    let committed = current_time
+        .checked_sub(staked_at)?
+        .clamp(0, CONVICTION_PERIOD) as u128;
+    let conviction_bps = (BASIS_POINTS.checked_sub(MIN_CONVICTION_WEIGHT_BPS)? as u128)
+        .checked_mul(committed)?
+        .checked_div(CONVICTION_PERIOD as u128)?
+        .checked_add(MIN_CONVICTION_WEIGHT_BPS as u128)?;
+    let cast = (voting_power as u128)
+        .checked_mul(conviction_bps)?
+        .checked_div(BASIS_POINTS as u128)?;
+    u64::try_from(cast).ok()
+}
+
+//commitment start of a stake after a top-up, averaged by voting power so fresh stake is not aged
+pub fn get_conviction_start(
+    staked_at: i64,
+    voting_power: u64,
+    added_voting_power: u64,
+    current_time: i64,
+) -> Option<i64> {
+    let total = (voting_power as i128).checked_add(added_voting_power as i128)?;
+    if total == 0 {
+        return Some(current_time);
+    }
+    let start = (staked_at as i128)
+        .checked_mul(voting_power as i128)?
+        .checked_add((current_time as i128).checked_mul(added_voting_power as i128)?)?
+        .checked_div(total)?;
+    i64::try_from(start).ok()
+}
+
+#[inline(always)]
+pub fn integer_sqrt(value: u64) -> u64 {
+    if value < 2 {
+        return value;
+    }
+    let mut root = value / 2 + 1;
+    let mut next = (root + value / root) / 2;
+    while next < root {
+        root = next;
+        next = (root + value / root) / 2;
+    }
+    root
+}
+
+//part of a position's voting power released when some of its stake is withdrawn
+pub fn get_released_voting_power(
+    voting_power: u64,
+    staked_amount: u64,
+    amount: u64,
+) -> Option<u64> {
+    let released = (voting_power as u128)
+        .checked_mul(amount as u128)?
+        .checked_div(staked_amount as u128)?;
+    u64::try_from(released).ok()
+}
+
+//a recipient's cut of what is left in a reward pool, the last claimer takes the remainder
+pub fn get_reward_share(pool_balance: u64, share_bps: u16, unclaimed_bps: u64) -> Option<u64> {
+    let reward = (pool_balance as u128)
+        .checked_mul(share_bps as u128)?
+        .checked_div(unclaimed_bps as u128)?;
+    u64::try_from(reward).ok()
+}
+
+//splits a reward into its (contributor, maintainer, issue reporter) parts
+pub fn get_reward_cuts(
+    amount: u64,
+    maintainer_cut_bps: u16,
+    issue_reporter_cut_bps: u16,
+) -> Option<(u64, u64, u64)> {
+    let maintainer_amount = (amount as u128)
+        .checked_mul(maintainer_cut_bps as u128)?
+        .checked_div(BASIS_POINTS as u128)?;
+    let issue_reporter_amount = (amount as u128)
+        .checked_mul(issue_reporter_cut_bps as u128)?
+        .checked_div(BASIS_POINTS as u128)?;
+    let contributor_amount = (amount as u128)
+        .checked_sub(maintainer_amount)?
+        .checked_sub(issue_reporter_amount)?;
+    Some((
+        u64::try_from(contributor_amount).ok()?,
+        u64::try_from(maintainer_amount).ok()?,
+        u64::try_from(issue_reporter_amount).ok()?,
+    ))
+}
+
+//labels are bounded so indexers can filter issues without fetching the uri
+pub fn validate_issue_labels(labels: &[String]) -> bool {
+    labels.len() <= MAX_ISSUE_LABELS
+        && labels
+            .iter()
+            .all(|label| !label.is_empty() && label.len() <= MAX_LABEL_LENGTH)
+        && labels
+            .iter()
+            .enumerate()
+            .all(|(index, label)| !labels[..index].contains(label))
+}
+
+//checks that a reward split names each recipient once and hands out exactly 100%
+pub fn validate_reward_split(split: &[RewardSplit]) -> bool {
+    let total_bps: u64 = split.iter().map(|share| share.share_bps as u64).sum();
+    !split.is_empty()
+        && split.len() <= MAX_REWARD_SHARES
+        && total_bps == BASIS_POINTS
+        && split.iter().all(|share| share.share_bps > 0)
+        && split.iter().enumerate().all(|(index, share)| {
+            split[..index]
+                .iter()
+                .all(|other| other.recipient != share.recipient)
+        })
+}
+
+//splits the fee charged on a trade into its (protocol, repository) parts
+pub fn calculate_trade_fee(usdc_amount: u64, trade_fee_bps: u16) -> Option<(u64, u64)> {
+    let total_fee = (usdc_amount as u128)
+        .checked_mul(trade_fee_bps as u128)?
+        .checked_div(BASIS_POINTS as u128)?;
+    let protocol_fee = total_fee
+        .checked_mul(PROTOCOL_FEE_SHARE_BPS as u128)?
+        .checked_div(BASIS_POINTS as u128)?;
+    let repository_fee = total_fee.checked_sub(protocol_fee)?;
+    Some((
+        u64::try_from(protocol_fee).ok()?,
+        u64::try_from(repository_fee).ok()?,
+    ))
+}
+
+pub fn validate_vesting_plan(plan: &VestingPlan) -> bool {
+    if plan.total_allocation == 0 {
+        return false;
+    }
+    match plan.mode {
+        VestingMode::Tranche => {
+            plan.number_of_tranches > 0
+                && (plan.number_of_tranches as usize) <= MAX_VESTING_SCHEDULES
+        }
+        VestingMode::Linear => plan.linear_duration > 0,
+    }
+}
+
+pub fn get_vesting_allocation(plan: &VestingPlan, decimals: u8) -> Option<u64> {
+    plan.total_allocation
+        .checked_mul(u64::checked_pow(10, decimals.into())?)
+}
+
+//returns the (start, end) timestamps over which the plan releases tokens
+pub fn get_vesting_window(plan: &VestingPlan, start_time: u64) -> Option<(u64, u64)> {
+    let vesting_start = start_time.checked_add(plan.cliff)?;
+    let vesting_length = match plan.mode {
+        VestingMode::Tranche => plan
+            .release_interval
+            .checked_mul((plan.number_of_tranches as u64).checked_sub(1)?)?,
+        VestingMode::Linear => plan.linear_duration,
     };
-    return false;
+    Some((vesting_start, vesting_start.checked_add(vesting_length)?))
+}
+
+//splits the vesting plan into tranches, any rounding remainder is released with the last one
+pub fn build_vesting_schedules(
+    plan: &VestingPlan,
+    start_time: u64,
+    decimals: u8,
+) -> Option<Vec<Schedule>> {
+    let mut schedules = vec![];
+    if plan.mode == VestingMode::Linear {
+        return Some(schedules);
+    }
+
+    let total_amount = get_vesting_allocation(plan, decimals)?;
+    let number_of_tranches = plan.number_of_tranches as u64;
+    let tranche_amount = total_amount / number_of_tranches;
+    let mut release_time = start_time.checked_add(plan.cliff)?;
+
+    for i in 0..number_of_tranches {
+        let amount = if i == number_of_tranches - 1 {
+            total_amount - tranche_amount * (number_of_tranches - 1)
+        } else {
+            tranche_amount
+        };
+        schedules.push(Schedule {
+            release_time,
+            amount,
+        });
+        release_time = release_time.checked_add(plan.release_interval)?;
+    }
+    Some(schedules)
+}
+
+//amount of a linear vesting schedule that has vested by `current_time`
+pub fn calculate_linear_vested(
+    total_amount: u64,
+    start_time: u64,
+    end_time: u64,
+    current_time: u64,
+) -> Option<u64> {
+    if current_time <= start_time {
+        return Some(0);
+    }
+    if current_time >= end_time {
+        return Some(total_amount);
+    }
+    let elapsed = (current_time - start_time) as u128;
+    let duration = (end_time - start_time) as u128;
+    u64::try_from((total_amount as u128).checked_mul(elapsed)? / duration).ok()
+}
+
+//amount streamed out of a linear vesting schedule that has not been released yet
+pub fn calculate_linear_release(
+    total_amount: u64,
+    released_amount: u64,
+    start_time: u64,
+    end_time: u64,
+    current_time: u64,
+) -> Option<u64> {
+    calculate_linear_vested(total_amount, start_time, end_time, current_time)?
+        .checked_sub(released_amount)
+}
+
+//main check code functions
+pub fn verify_calc_buy(
+    curve: &CurveConfig,
+    token_supply: u64,
+    number_of_lamports: u64,
+    token_amount: u64,
+) -> bool {
+    match calculate_buy_amount(curve, token_supply, token_amount) {
+        Some(value) => check_equal_buy(number_of_lamports as u128, value),
+        None => false,
+    }
+}
+
+pub fn verify_calc_sell(
+    curve: &CurveConfig,
+    token_supply: u64,
+    number_of_lamports: u64,
+    token_amount: u64,
+) -> bool {
+    match calculate_sell_amount(curve, token_supply, token_amount) {
+        Some(value) => (number_of_lamports as u128) == value,
+        None => false,
+    }
+}
+
+//prices buying `number_of_tokens` raw tokens from the live supply, trade and transfer fee on top
+pub fn get_buy_quote(
+    repository: &Repository,
+    token_supply: u64,
+    decimals: u8,
+    number_of_tokens: u64,
+    usdc_mint: &AccountInfo,
+    epoch: u64,
+) -> Option<TradeQuote> {
+    let modified_token_supply =
+        get_modified_supply(token_supply, repository.vesting_allocation, decimals)?;
+    let modified_tokens = get_modified_tokens(number_of_tokens, decimals)?;
+    let usdc_amount = u64::try_from(calculate_buy_amount(
+        &repository.curve_config,
+        modified_token_supply,
+        modified_tokens,
+    )?)
+    .ok()?;
+    let (protocol_fee, repository_fee) =
+        calculate_trade_fee(usdc_amount, repository.trade_fee_bps)?;
+    //matches execute_buy, the reserve has to receive the full curve price
+    let transfer_fee = get_inverse_transfer_fee(usdc_mint, epoch, usdc_amount)?;
+    Some(TradeQuote {
+        number_of_tokens,
+        usdc_amount,
+        protocol_fee,
+        repository_fee,
+        transfer_fee,
+        total_usdc: usdc_amount
+            .checked_add(transfer_fee)?
+            .checked_add(protocol_fee)?
+            .checked_add(repository_fee)?,
+    })
 }
 
+//prices selling `number_of_tokens` raw tokens into the live supply, fees taken from the payout
+pub fn get_sell_quote(
+    repository: &Repository,
+    token_supply: u64,
+    decimals: u8,
+    number_of_tokens: u64,
+    usdc_mint: &AccountInfo,
+    epoch: u64,
+) -> Option<TradeQuote> {
+    let modified_token_supply =
+        get_modified_supply(token_supply, repository.vesting_allocation, decimals)?;
+    let modified_tokens = get_modified_tokens(number_of_tokens, decimals)?;
+    let usdc_amount = u64::try_from(calculate_sell_amount(
+        &repository.curve_config,
+        modified_token_supply,
+        modified_tokens,
+    )?)
+    .ok()?;
+    let (protocol_fee, repository_fee) =
+        calculate_trade_fee(usdc_amount, repository.trade_fee_bps)?;
+    let seller_payout = usdc_amount
+        .checked_sub(protocol_fee)?
+        .checked_sub(repository_fee)?;
+    let transfer_fee = get_transfer_fee(usdc_mint, epoch, seller_payout)?;
+    Some(TradeQuote {
+        number_of_tokens,
+        usdc_amount,
+        protocol_fee,
+        repository_fee,
+        transfer_fee,
+        total_usdc: seller_payout.checked_sub(transfer_fee)?,
+    })
+}
+
+//checks that selling token_amount1 on curve1 releases enough reserve to buy token_amount2 on curve2
 pub fn verify_swap(
+    curve1: &CurveConfig,
+    curve2: &CurveConfig,
     token_supply1: u64,
     token_supply2: u64,
     token_amount1: u64,
     token_amount2: u64,
 ) -> bool {
-    let value1 = calculate_sell_amount(token_supply1, token_amount1);
-    let value2 = calculate_buy_amount(token_supply2, token_amount2);
-    if value1 == value2 {
-        return true;
+    let value1 = calculate_sell_amount(curve1, token_supply1, token_amount1);
+    let value2 = calculate_buy_amount(curve2, token_supply2, token_amount2);
+    if let (Some(value1), Some(value2)) = (value1, value2) {
+        return value2 <= value1;
     }
     false
 }
 
+//largest whole token amount whose curve price plus trade fee fits into `budget`, with that price
+pub fn calculate_swap_output(
+    curve: &CurveConfig,
+    token_supply: u64,
+    budget: u64,
+    trade_fee_bps: u16,
+) -> Option<(u64, u64)> {
+    let total_cost = |token_amount: u64| -> Option<u64> {
+        let price = u64::try_from(calculate_buy_amount(curve, token_supply, token_amount)?).ok()?;
+        let (protocol_fee, repository_fee) = calculate_trade_fee(price, trade_fee_bps)?;
+        price.checked_add(protocol_fee)?.checked_add(repository_fee)
+    };
+    let fits = |token_amount: u64| matches!(total_cost(token_amount), Some(cost) if cost <= budget);
+
+    //doubles until the budget is exceeded, then bisects between the last two bounds
+    let mut low: u64 = 0;
+    let mut high: u64 = 1;
+    while fits(high) {
+        low = high;
+        high = high.checked_mul(2)?;
+    }
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if fits(mid) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    let price = u64::try_from(calculate_buy_amount(curve, token_supply, low)?).ok()?;
+    Some((low, price))
+}
+
 pub const PREFIX: &str = "metadata";
 
+//fee withheld by a token-2022 transfer fee extension when `amount` is sent, zero for other mints
+pub fn get_transfer_fee(mint: &AccountInfo, epoch: u64, amount: u64) -> Option<u64> {
+    if !mint.owner.eq(&spl_token_2022::ID) {
+        return Some(0);
+    }
+    let mint_data = mint.try_borrow_data().ok()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data).ok()?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config.calculate_epoch_fee(epoch, amount),
+        Err(_) => Some(0),
+    }
+}
+
+//fee that has to be added on top of `amount` so that `amount` arrives after the transfer fee
+pub fn get_inverse_transfer_fee(mint: &AccountInfo, epoch: u64, amount: u64) -> Option<u64> {
+    if !mint.owner.eq(&spl_token_2022::ID) {
+        return Some(0);
+    }
+    let mint_data = mint.try_borrow_data().ok()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data).ok()?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config.calculate_inverse_epoch_fee(epoch, amount),
+        Err(_) => Some(0),
+    }
+}
+
+//amount that actually lands in the destination account after the transfer fee
+pub fn get_amount_after_transfer_fee(mint: &AccountInfo, epoch: u64, amount: u64) -> Option<u64> {
+    amount.checked_sub(get_transfer_fee(mint, epoch, amount)?)
+}
+
 pub fn find_metadata_account(mint: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[PREFIX.as_bytes(), ID.as_ref(), mint.as_ref()], &ID)
 }
@@ -1,7 +1,8 @@
 use crate::{
+    constants::BASIS_POINTS,
     error::DefiOSError,
     event::IssueMergedByVote,
-    state::{Issue, PullRequest, Repository},
+    state::{Issue, ProgramConfig, PullRequest, Repository, RewardShare},
 };
 use anchor_lang::prelude::*;
 
@@ -32,6 +33,11 @@ pub struct AcceptIssueVote<'info> {
         bump = pull_request_metadata_account.bump
     )]
     pub pull_request_metadata_account: Account<'info, PullRequest>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -40,14 +46,46 @@ pub fn handler(ctx: Context<AcceptIssueVote>) -> Result<()> {
     let issue = &mut ctx.accounts.issue;
     let repository = &mut ctx.accounts.repository_account;
 
-    let majority_threshhold = issue.total_stake_amount / 2;
+    //re-accepting would hand out the reward a second time
+    require!(issue.closed_at.is_none(), DefiOSError::IssueClosedAlready);
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let first_pr_time = issue.first_pr_time.ok_or(DefiOSError::NoPRFound)?;
+    let governance_config = repository.governance_config;
+    let voting_open = current_time - first_pr_time <= ctx.accounts.program_config.voting_end;
+
+    //total voting power is tallied in the issue's voting mode, conviction never exceeds it
+    require!(
+        !voting_open || governance_config.allow_early_finalisation,
+        DefiOSError::VotingPeriodOnGoing
+    );
+    //the quorum applies whether or not voting is still open
+    require!(
+        (issue.total_votes_cast as u128) * (BASIS_POINTS as u128)
+            >= (issue.total_voting_power as u128) * (governance_config.quorum_bps as u128),
+        DefiOSError::QuorumNotReached
+    );
+    //while voting is open, or without a quorum, the pull request has to clear the threshold of
+    //all voting power, once voting has closed on a met quorum only the votes actually cast count
+    let approval_base = if voting_open || governance_config.quorum_bps == 0 {
+        issue.total_voting_power
+    } else {
+        issue.total_votes_cast
+    };
 
     require!(
-        pull_request_metadata_account.total_voted_amount > majority_threshhold,
+        (pull_request_metadata_account.total_voted_amount as u128) * (BASIS_POINTS as u128)
+            > (approval_base as u128) * (governance_config.approval_threshold_bps as u128),
         DefiOSError::NotEnoughVotesForIssueMerge
     );
 
-    issue.closed_at = Some(Clock::get()?.unix_timestamp);
+    issue.closed_at = Some(current_time);
+    //the accepted pull request takes the whole reward
+    issue.reward_shares = vec![RewardShare {
+        recipient: pull_request_metadata_account.sent_by,
+        share_bps: BASIS_POINTS as u16,
+        claimed: false,
+    }];
 
     pull_request_metadata_account.accepted = true;
 
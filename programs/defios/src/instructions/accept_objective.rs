@@ -1,7 +1,6 @@
-use crate::constants::VOTING_END;
 use crate::error::DefiOSError;
 use crate::event::ObjectiveAccepted;
-use crate::state::{Objective, ObjectiveProposal, Repository};
+use crate::state::{Objective, ObjectiveProposal, ProgramConfig, Repository};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -35,6 +34,11 @@ pub struct AcceptObjective<'info> {
         bump = repository_account.bump
     )]
     pub repository_account: Account<'info, Repository>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -45,7 +49,7 @@ pub fn handler(ctx: Context<AcceptObjective>) -> Result<()> {
 
     let current_time = Clock::get()?.unix_timestamp;
     require!(
-        current_time - objective_proposal.proposed_at > VOTING_END,
+        current_time - objective_proposal.proposed_at > ctx.accounts.program_config.voting_end,
         DefiOSError::VotingPeriodOnGoing
     );
 
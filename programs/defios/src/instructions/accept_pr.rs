@@ -1,6 +1,7 @@
+use crate::constants::BASIS_POINTS;
 use crate::error::DefiOSError;
 use crate::event::PullRequestAccepted;
-use crate::state::{Issue, PullRequest, Repository};
+use crate::state::{Issue, PullRequest, Repository, RewardShare};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -44,8 +45,17 @@ pub fn handler(ctx: Context<AcceptPullRequest>, repo_name: String) -> Result<()>
     let repository = &mut ctx.accounts.repository_account;
     let repository_creator = &ctx.accounts.repository_creator;
     let pull_request_metadata_account = &mut ctx.accounts.pull_request_metadata_account;
+    //re-accepting would hand out the reward a second time
+    require!(issue.closed_at.is_none(), DefiOSError::IssueClosedAlready);
+
     pull_request_metadata_account.accepted = true;
     issue.closed_at = Some(Clock::get()?.unix_timestamp);
+    //the accepted pull request takes the whole reward
+    issue.reward_shares = vec![RewardShare {
+        recipient: pull_request_addr.key(),
+        share_bps: BASIS_POINTS as u16,
+        claimed: false,
+    }];
 
     repository.num_open_issues -= 1;
 
@@ -0,0 +1,96 @@
+use crate::{
+    error::DefiOSError,
+    event::RewardSplitAccepted,
+    helper::validate_reward_split,
+    state::{Issue, PullRequest, Repository, RewardShare, RewardSplit},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptRewardSplit<'info> {
+    #[account(
+        address = repository_account.repository_creator @ DefiOSError::UnauthorizedUser,
+    )]
+    pub repository_creator: Signer<'info>,
+    #[account(
+        mut,
+        address = issue_account.repository,
+        seeds = [
+            b"repository",
+            repository_account.id.as_bytes(),
+            repository_creator.key().as_ref(),
+        ],
+        bump = repository_account.bump
+    )]
+    pub repository_account: Box<Account<'info, Repository>>,
+    #[account(
+        mut,
+        seeds = [
+            b"issue",
+            issue_account.index.to_string().as_bytes(),
+            repository_account.key().as_ref(),
+            issue_account.issue_creator.key().as_ref(),
+        ],
+        bump = issue_account.bump
+    )]
+    pub issue_account: Box<Account<'info, Issue>>,
+}
+
+//remaining accounts are the pull requests of recipients that opened one, they get marked accepted
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AcceptRewardSplit<'info>>,
+    reward_split: Vec<RewardSplit>,
+) -> Result<()> {
+    let repository_account = &mut ctx.accounts.repository_account;
+    let issue_account = &mut ctx.accounts.issue_account;
+
+    require!(
+        issue_account.closed_at.is_none(),
+        DefiOSError::IssueClosedAlready
+    );
+    require!(
+        validate_reward_split(&reward_split),
+        DefiOSError::InvalidRewardShares
+    );
+
+    for pull_request_info in ctx.remaining_accounts.iter() {
+        let mut pull_request = Account::<PullRequest>::try_from(pull_request_info)?;
+        let (expected_pull_request, _) = Pubkey::find_program_address(
+            &[
+                b"pullrequestadded",
+                issue_account.key().as_ref(),
+                pull_request.sent_by.as_ref(),
+            ],
+            ctx.program_id,
+        );
+        require!(
+            expected_pull_request.eq(&pull_request_info.key())
+                && reward_split
+                    .iter()
+                    .any(|share| share.recipient == pull_request.sent_by),
+            DefiOSError::NoRewardShare
+        );
+        pull_request.accepted = true;
+        pull_request.exit(ctx.program_id)?;
+    }
+
+    issue_account.closed_at = Some(Clock::get()?.unix_timestamp);
+    issue_account.reward_shares = reward_split
+        .iter()
+        .map(|share| RewardShare {
+            recipient: share.recipient,
+            share_bps: share.share_bps,
+            claimed: false,
+        })
+        .collect();
+    repository_account.num_open_issues -= 1;
+
+    emit!(RewardSplitAccepted {
+        issue: issue_account.key(),
+        repository: repository_account.key(),
+        recipients: reward_split.iter().map(|share| share.recipient).collect(),
+        shares_bps: reward_split.iter().map(|share| share.share_bps).collect()
+    });
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use crate::{
+    constants::{BASIS_POINTS, MAX_BOUNTY_MINTS},
+    error::DefiOSError,
+    event::BountyMintAdded,
+    state::{BountyPool, Issue, Repository},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct AddBountyMint<'info> {
+    #[account(
+        address = repository_account.repository_creator @ DefiOSError::UnauthorizedUser,
+    )]
+    pub repository_creator: Signer<'info>,
+    #[account(
+        address = issue_account.repository,
+        seeds = [
+            b"repository",
+            repository_account.id.as_bytes(),
+            repository_creator.key().as_ref(),
+        ],
+        bump = repository_account.bump
+    )]
+    pub repository_account: Box<Account<'info, Repository>>,
+    #[account(
+        mut,
+        seeds = [
+            b"issue",
+            issue_account.index.to_string().as_bytes(),
+            repository_account.key().as_ref(),
+            issue_account.issue_creator.key().as_ref(),
+        ],
+        bump = issue_account.bump
+    )]
+    pub issue_account: Account<'info, Issue>,
+    pub bounty_mint: InterfaceAccount<'info, Mint>,
+    #[account(address = issue_account.issue_token)]
+    pub issue_token: InterfaceAccount<'info, Mint>,
+}
+
+//voting_weight_bps is capped at 10000, so once both are brought to the same decimals
+//a bounty token never carries more say than a repository token
+pub fn handler(ctx: Context<AddBountyMint>, voting_weight_bps: u16) -> Result<()> {
+    let issue_account = &mut ctx.accounts.issue_account;
+    let bounty_mint = &ctx.accounts.bounty_mint;
+    let issue_token = &ctx.accounts.issue_token;
+
+    require!(
+        issue_account.closed_at.is_none(),
+        DefiOSError::IssueClosedAlready
+    );
+    require!(
+        (voting_weight_bps as u64) <= BASIS_POINTS,
+        DefiOSError::InvalidVotingWeight
+    );
+    require!(
+        bounty_mint.key() != issue_account.issue_token
+            && !issue_account
+                .bounty_pools
+                .iter()
+                .any(|pool| pool.mint == bounty_mint.key()),
+        DefiOSError::BountyMintAlreadyAdded
+    );
+    require!(
+        issue_account.bounty_pools.len() < MAX_BOUNTY_MINTS,
+        DefiOSError::TooManyBountyMints
+    );
+
+    issue_account.bounty_pools.push(BountyPool {
+        mint: bounty_mint.key(),
+        voting_weight_bps,
+        total_stake_amount: 0,
+        mint_decimals: bounty_mint.decimals,
+        issue_token_decimals: issue_token.decimals,
+    });
+
+    emit!(BountyMintAdded {
+        issue: issue_account.key(),
+        bounty_mint: bounty_mint.key(),
+        voting_weight_bps: voting_weight_bps
+    });
+
+    Ok(())
+}
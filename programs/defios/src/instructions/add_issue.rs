@@ -1,7 +1,8 @@
 use crate::{
     error::DefiOSError,
     event::IssueCreated,
-    state::{Issue, Repository, VerifiedUser},
+    helper::validate_issue_labels,
+    state::{Issue, IssueDifficulty, IssuePriority, Repository, VerifiedUser},
 };
 use anchor_lang::prelude::*;
 
@@ -50,19 +51,44 @@ pub struct AddIssue<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<AddIssue>, uri: String) -> Result<()> {
+pub fn handler(
+    ctx: Context<AddIssue>,
+    uri: String,
+    deadline: Option<i64>,
+    labels: Vec<String>,
+    priority: Option<IssuePriority>,
+    difficulty: Option<IssueDifficulty>,
+) -> Result<()> {
     let repository_account = &mut ctx.accounts.repository_account;
     let issue_account = &mut ctx.accounts.issue_account;
     let issue_creator = &ctx.accounts.issue_creator;
+    let created_at = Clock::get()?.unix_timestamp;
+
+    if let Some(deadline) = deadline {
+        require!(deadline > created_at, DefiOSError::InvalidIssueDeadline);
+    }
+    require!(
+        validate_issue_labels(&labels),
+        DefiOSError::InvalidIssueLabels
+    );
 
     issue_account.bump = ctx.bumps.issue_account;
     issue_account.index = repository_account.issue_index;
-    issue_account.created_at = Clock::get()?.unix_timestamp;
+    issue_account.created_at = created_at;
     issue_account.issue_creator = issue_creator.key();
     issue_account.repository = repository_account.key();
     issue_account.uri = uri;
     issue_account.closed_at = None;
     issue_account.issue_token = repository_account.repo_token;
+    issue_account.deadline = deadline;
+    issue_account.labels = labels;
+    issue_account.priority = priority.unwrap_or_default();
+    issue_account.difficulty = difficulty.unwrap_or_default();
+    //the mode is fixed per issue so its tallies stay consistent if the repository switches
+    issue_account.voting_mode = repository_account.voting_mode;
+    //cuts are fixed when the bounty is posted so they can't be raised before a claim
+    issue_account.maintainer_cut_bps = repository_account.maintainer_cut_bps;
+    issue_account.issue_reporter_cut_bps = repository_account.issue_reporter_cut_bps;
     repository_account.issue_index += 1;
     repository_account.num_open_issues += 1;
 
@@ -70,7 +96,11 @@ pub fn handler(ctx: Context<AddIssue>, uri: String) -> Result<()> {
         issue_creator: issue_creator.key(),
         issue_account: issue_account.key(),
         repository_account: repository_account.key(),
-        uri: issue_account.uri.clone()
+        uri: issue_account.uri.clone(),
+        deadline: deadline,
+        labels: issue_account.labels.clone(),
+        priority: issue_account.priority,
+        difficulty: issue_account.difficulty
     });
 
     Ok(())
@@ -44,6 +44,7 @@ pub fn handler(ctx: Context<AddPullRequest>, metadata_uri: String) -> Result<()>
     pull_request_metadata_account.sent_by = pull_request_addr.key();
     pull_request_metadata_account.metadata_uri = metadata_uri.clone();
     pull_request_metadata_account.accepted = false;
+    pull_request_metadata_account.voter_count = 0;
 
     if issue.first_pr_time == None {
         issue.first_pr_time = Some(Clock::get()?.unix_timestamp);
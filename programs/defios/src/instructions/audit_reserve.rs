@@ -0,0 +1,76 @@
+use crate::curve::{check_reserve, reserve_at};
+use crate::error::DefiOSError;
+use crate::event::ReserveAudited;
+use crate::helper::get_modified_supply;
+use crate::state::{CommunalAccount, ProgramConfig, Repository};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct AuditReserve<'info> {
+    pub repository_account: Box<Account<'info, Repository>>,
+    #[account(
+        seeds = [
+            b"Miners",
+            b"MinerC",
+            repository_account.key().as_ref()
+        ],
+        bump
+    )]
+    pub rewards_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [
+            b"are_we_conscious",
+            b"is love life ?  ",
+            b"arewemadorinlove",
+            rewards_mint.key().as_ref()
+        ],
+        bump = communal_deposit.bump
+    )]
+    pub communal_deposit: Account<'info, CommunalAccount>,
+    #[account(
+        constraint = communal_usdc_account.mint == program_config.quote_mint @ DefiOSError::InvalidQuoteMint,
+        constraint = communal_usdc_account.owner == communal_deposit.key()
+    )]
+    pub communal_usdc_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+}
+
+pub fn handler(ctx: Context<AuditReserve>) -> Result<()> {
+    let repository_account = &ctx.accounts.repository_account;
+    let rewards_mint = &ctx.accounts.rewards_mint;
+    let communal_usdc_account = &ctx.accounts.communal_usdc_account;
+
+    //recomputes the reserve the curve implies for the circulating supply
+    let circulating_supply = get_modified_supply(
+        rewards_mint.supply,
+        repository_account.vesting_allocation,
+        rewards_mint.decimals,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+    let required_reserve = reserve_at(&repository_account.curve_config, circulating_supply)
+        .and_then(|amount| u64::try_from(amount).ok())
+        .ok_or(DefiOSError::MathOverflow)?;
+    let (surplus, deficit) = check_reserve(
+        &repository_account.curve_config,
+        circulating_supply,
+        communal_usdc_account.amount,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+
+    emit!(ReserveAudited {
+        repository: repository_account.key(),
+        rewards_mint: rewards_mint.key(),
+        circulating_supply: circulating_supply,
+        required_reserve: required_reserve,
+        reserve_balance: communal_usdc_account.amount,
+        surplus: u64::try_from(surplus).map_err(|_| DefiOSError::MathOverflow)?,
+        deficit: u64::try_from(deficit).map_err(|_| DefiOSError::MathOverflow)?
+    });
+
+    Ok(())
+}
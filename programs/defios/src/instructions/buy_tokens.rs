@@ -1,17 +1,22 @@
-use crate::constants::{MAX_INT, TOKEN_VEST_AMOUNT, VESTING_NUMBER};
+use crate::constants::MAX_INT;
+use crate::curve::validate_curve;
 use crate::error::DefiOSError;
-use crate::helper::verify_calc_buy;
-use crate::state::{CommunalAccount, Repository};
+use crate::event::{TokensBought, TradeFeeCollected};
+use crate::helper::{
+    calculate_trade_fee, get_inverse_transfer_fee, get_modified_supply, get_modified_tokens,
+    is_whole_token_amount, verify_calc_buy,
+};
+use crate::state::{CommunalAccount, ProgramConfig, Repository, Treasury};
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::{create, get_associated_token_address, AssociatedToken, Create},
-    mint::USDC,
-    token,
-    token::{transfer, Mint, Token, TokenAccount, Transfer},
+    associated_token::{
+        create, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_interface,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 #[derive(Accounts)]
-#[instruction(usdc_amount:u64)]
 pub struct BuyToken<'info> {
     #[account(mut)]
     pub buyer: Signer<'info>,
@@ -30,13 +35,13 @@ pub struct BuyToken<'info> {
         constraint=communal_token_account.mint==rewards_mint.key(),
         constraint = communal_token_account.owner == communal_deposit.key()
     )]
-    pub communal_token_account: Account<'info, TokenAccount>,
+    pub communal_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         constraint=communal_usdc_account.mint==usdc_mint.key(),
         constraint = communal_usdc_account.owner == communal_deposit.key()
     )]
-    pub communal_usdc_account: Account<'info, TokenAccount>,
+    pub communal_usdc_account: InterfaceAccount<'info, TokenAccount>,
     ///CHECK: Check for this account done in function call
     #[account(mut)]
     pub buyer_token_account: AccountInfo<'info>,
@@ -44,12 +49,11 @@ pub struct BuyToken<'info> {
         mut,
         constraint=buyer_usdc_account.mint==usdc_mint.key(),
         constraint = buyer_usdc_account.owner == buyer.key(),
-        constraint = buyer_usdc_account.amount >= usdc_amount @DefiOSError::InsufficientFunds
     )]
-    pub buyer_usdc_account: Account<'info, TokenAccount>,
+    pub buyer_usdc_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub repository_account: Box<Account<'info, Repository>>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     #[account(
         mut,
         seeds = [
@@ -59,14 +63,85 @@ pub struct BuyToken<'info> {
         ],
         bump
     )]
-    pub rewards_mint: Account<'info, Mint>,
-    // #[account(address=USDC)]
-    pub usdc_mint: Account<'info, Mint>,
+    pub rewards_mint: InterfaceAccount<'info, Mint>,
+    #[account(address = program_config.quote_mint @ DefiOSError::InvalidQuoteMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [
+            b"treasury",
+            repository_account.key().as_ref()
+        ],
+        bump = repository_treasury.bump
+    )]
+    pub repository_treasury: Box<Account<'info, Treasury>>,
+    #[account(
+        mut,
+        constraint = repository_treasury_usdc_account.mint == usdc_mint.key(),
+        constraint = repository_treasury_usdc_account.owner == repository_treasury.key()
+    )]
+    pub repository_treasury_usdc_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            b"treasury",
+            b"protocol"
+        ],
+        bump = protocol_treasury.bump
+    )]
+    pub protocol_treasury: Box<Account<'info, Treasury>>,
+    #[account(
+        mut,
+        constraint = protocol_treasury_usdc_account.mint == usdc_mint.key(),
+        constraint = protocol_treasury_usdc_account.owner == protocol_treasury.key()
+    )]
+    pub protocol_treasury_usdc_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub usdc_token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<BuyToken>, usdc_amount: u64, number_of_tokens: u64) -> Result<()> {
+    let rewards_mint = &ctx.accounts.rewards_mint;
+    let repository_account = &ctx.accounts.repository_account;
+
+    require!(
+        validate_curve(&repository_account.curve_config),
+        DefiOSError::RepositoryNotMigrated
+    );
+    let modified_token_supply = get_modified_supply(
+        rewards_mint.supply,
+        repository_account.vesting_allocation,
+        rewards_mint.decimals,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+    let modified_tokens = get_modified_tokens(number_of_tokens, rewards_mint.decimals)
+        .ok_or(DefiOSError::MathOverflow)?;
+    require!(
+        (number_of_tokens as u128) < MAX_INT,
+        DefiOSError::MathOverflow
+    );
+    require!(
+        verify_calc_buy(
+            &repository_account.curve_config,
+            modified_token_supply,
+            usdc_amount,
+            modified_tokens
+        ),
+        DefiOSError::IncorrectMaths
+    );
+
+    execute_buy(ctx, usdc_amount, number_of_tokens)?;
+
+    Ok(())
+}
+
+//moves usdc into the communal account and mints the bought tokens to the buyer,
+//returns the usdc debited from the buyer
+pub fn execute_buy(ctx: Context<BuyToken>, usdc_amount: u64, number_of_tokens: u64) -> Result<u64> {
     let token_program = &ctx.accounts.token_program;
     let buyer = &mut ctx.accounts.buyer;
     let communal_deposit = &mut ctx.accounts.communal_deposit;
@@ -78,20 +153,39 @@ pub fn handler(ctx: Context<BuyToken>, usdc_amount: u64, number_of_tokens: u64)
     let repository_account = &ctx.accounts.repository_account;
     let buyer_usdc_account = &mut ctx.accounts.buyer_usdc_account;
     let communal_usdc_account = &mut ctx.accounts.communal_usdc_account;
+    let repository_treasury_usdc_account = &ctx.accounts.repository_treasury_usdc_account;
+    let protocol_treasury_usdc_account = &ctx.accounts.protocol_treasury_usdc_account;
+    let usdc_mint = &ctx.accounts.usdc_mint;
+    let usdc_token_program = &ctx.accounts.usdc_token_program;
 
-    let total = TOKEN_VEST_AMOUNT * VESTING_NUMBER;
-    let token_supply = rewards_mint.supply;
-    let modified_token_supply: u64 =
-        (token_supply - total) / (u64::pow(10, rewards_mint.decimals.into()));
-    let modified_tokens: u64 = number_of_tokens / (u64::pow(10, rewards_mint.decimals.into()));
+    //the curve prices whole tokens, a fraction of one would be minted for nothing
     require!(
-        (number_of_tokens as u128) < MAX_INT,
-        DefiOSError::MathOverflow
+        is_whole_token_amount(number_of_tokens, rewards_mint.decimals)
+            .ok_or(DefiOSError::MathOverflow)?,
+        DefiOSError::FractionalTokenAmount
     );
+
+    //trade fee is charged on top of the curve price
+    let (protocol_fee, repository_fee) =
+        calculate_trade_fee(usdc_amount, repository_account.trade_fee_bps)
+            .ok_or(DefiOSError::MathOverflow)?;
+    //any usdc transfer fee is also paid by the buyer so the reserve receives the full curve price
+    let reserve_deposit = get_inverse_transfer_fee(
+        &usdc_mint.to_account_info(),
+        Clock::get()?.epoch,
+        usdc_amount,
+    )
+    .and_then(|transfer_fee| usdc_amount.checked_add(transfer_fee))
+    .ok_or(DefiOSError::MathOverflow)?;
+    let total_payment = reserve_deposit
+        .checked_add(protocol_fee)
+        .and_then(|amount| amount.checked_add(repository_fee))
+        .ok_or(DefiOSError::MathOverflow)?;
     require!(
-        verify_calc_buy(modified_token_supply, usdc_amount, modified_tokens),
-        DefiOSError::IncorrectMaths
+        buyer_usdc_account.amount >= total_payment,
+        DefiOSError::InsufficientFunds
     );
+
     let rewards_key = rewards_mint.key();
     //checks if buyer has token account else creates it
     if buyer_token_account.data_is_empty() {
@@ -107,25 +201,62 @@ pub fn handler(ctx: Context<BuyToken>, usdc_amount: u64, number_of_tokens: u64)
             },
         ))?;
     }
-    let expected_buyer_token_account =
-        get_associated_token_address(&buyer.key(), &rewards_mint.key());
+    let expected_buyer_token_account = get_associated_token_address_with_program_id(
+        &buyer.key(),
+        &rewards_mint.key(),
+        &token_program.key(),
+    );
     require!(
         expected_buyer_token_account.eq(&buyer_token_account.key()),
         DefiOSError::TokenAccountMismatch
     );
     //execute function to send usdc to communal deposits
-    transfer(
+    transfer_checked(
         CpiContext::new(
-            token_program.to_account_info(),
-            Transfer {
+            usdc_token_program.to_account_info(),
+            TransferChecked {
                 from: buyer_usdc_account.to_account_info(),
+                mint: usdc_mint.to_account_info(),
                 to: communal_usdc_account.to_account_info(),
                 authority: buyer.to_account_info(),
             },
         ),
-        usdc_amount,
+        reserve_deposit,
+        usdc_mint.decimals,
     )?;
 
+    //execute function to send fees to the treasuries
+    if protocol_fee > 0 {
+        transfer_checked(
+            CpiContext::new(
+                usdc_token_program.to_account_info(),
+                TransferChecked {
+                    from: buyer_usdc_account.to_account_info(),
+                    mint: usdc_mint.to_account_info(),
+                    to: protocol_treasury_usdc_account.to_account_info(),
+                    authority: buyer.to_account_info(),
+                },
+            ),
+            protocol_fee,
+            usdc_mint.decimals,
+        )?;
+    }
+    if repository_fee > 0 {
+        transfer_checked(
+            CpiContext::new(
+                usdc_token_program.to_account_info(),
+                TransferChecked {
+                    from: buyer_usdc_account.to_account_info(),
+                    mint: usdc_mint.to_account_info(),
+                    to: repository_treasury_usdc_account.to_account_info(),
+                    authority: buyer.to_account_info(),
+                },
+            ),
+            repository_fee,
+            usdc_mint.decimals,
+        )?;
+    }
+
     //mints required number of tokens
     let bump = ctx.bumps.rewards_mint;
     let repository_account_key = repository_account.key();
@@ -136,10 +267,10 @@ pub fn handler(ctx: Context<BuyToken>, usdc_amount: u64, number_of_tokens: u64)
         &[bump],
     ]];
 
-    token::mint_to(
+    token_interface::mint_to(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            token::MintTo {
+            token_interface::MintTo {
                 mint: rewards_mint.to_account_info(),
                 to: communal_token_account.to_account_info(),
                 authority: rewards_mint.to_account_info(),
@@ -157,18 +288,36 @@ pub fn handler(ctx: Context<BuyToken>, usdc_amount: u64, number_of_tokens: u64)
         &[communal_deposit.bump],
     ]];
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: communal_token_account.to_account_info(),
+                mint: rewards_mint.to_account_info(),
                 to: buyer_token_account.to_account_info(),
                 authority: communal_deposit.to_account_info(),
             },
             communal_signer_seeds,
         ),
         number_of_tokens,
+        rewards_mint.decimals,
     )?;
 
-    Ok(())
+    emit!(TradeFeeCollected {
+        repository: repository_account.key(),
+        trader: buyer.key(),
+        rewards_mint: rewards_key,
+        protocol_fee: protocol_fee,
+        repository_fee: repository_fee
+    });
+
+    emit!(TokensBought {
+        buyer: buyer.key(),
+        repository: repository_account.key(),
+        rewards_mint: rewards_key,
+        number_of_tokens: number_of_tokens,
+        usdc_amount: usdc_amount
+    });
+
+    Ok(total_payment)
 }
@@ -0,0 +1,32 @@
+use crate::curve::validate_curve;
+use crate::error::DefiOSError;
+use crate::helper::get_buy_quote;
+use crate::instructions::buy_tokens::{execute_buy, BuyToken};
+use anchor_lang::prelude::*;
+
+pub fn handler(ctx: Context<BuyToken>, number_of_tokens: u64, max_usdc_in: u64) -> Result<()> {
+    let rewards_mint = &ctx.accounts.rewards_mint;
+    let repository_account = &ctx.accounts.repository_account;
+
+    require!(
+        validate_curve(&repository_account.curve_config),
+        DefiOSError::RepositoryNotMigrated
+    );
+
+    //prices the trade against the live supply instead of trusting the client
+    let quote = get_buy_quote(
+        repository_account,
+        rewards_mint.supply,
+        rewards_mint.decimals,
+        number_of_tokens,
+        &ctx.accounts.usdc_mint.to_account_info(),
+        Clock::get()?.epoch,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+
+    //bounds what actually leaves the buyer, transfer fee included
+    let total_payment = execute_buy(ctx, quote.usdc_amount, number_of_tokens)?;
+    require!(total_payment <= max_usdc_in, DefiOSError::SlippageExceeded);
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::{
+    error::DefiOSError,
+    event::IssueCancelled,
+    state::{Issue, Repository},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelIssue<'info> {
+    #[account(
+        constraint = authority.key() == issue_account.issue_creator
+            || authority.key() == repository_account.repository_creator
+            @ DefiOSError::UnauthorizedUser,
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        address = issue_account.repository,
+        seeds = [
+            b"repository",
+            repository_account.id.as_bytes(),
+            repository_account.repository_creator.key().as_ref(),
+        ],
+        bump = repository_account.bump
+    )]
+    pub repository_account: Box<Account<'info, Repository>>,
+    #[account(
+        mut,
+        seeds = [
+            b"issue",
+            issue_account.index.to_string().as_bytes(),
+            repository_account.key().as_ref(),
+            issue_account.issue_creator.key().as_ref(),
+        ],
+        bump = issue_account.bump
+    )]
+    pub issue_account: Account<'info, Issue>,
+}
+
+pub fn handler(ctx: Context<CancelIssue>) -> Result<()> {
+    let authority = &ctx.accounts.authority;
+    let repository_account = &mut ctx.accounts.repository_account;
+    let issue_account = &mut ctx.accounts.issue_account;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(
+        issue_account.closed_at.is_none(),
+        DefiOSError::IssueClosedAlready
+    );
+
+    //closing the issue stops new pull requests and stakes, stakers can only withdraw
+    issue_account.closed_at = Some(current_time);
+    issue_account.refund_only = true;
+    repository_account.num_open_issues -= 1;
+
+    emit!(IssueCancelled {
+        issue: issue_account.key(),
+        repository: repository_account.key(),
+        cancelled_by: authority.key(),
+        cancelled_at: current_time,
+        total_stake_amount: issue_account.total_stake_amount
+    });
+
+    Ok(())
+}
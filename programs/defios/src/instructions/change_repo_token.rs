@@ -1,15 +1,20 @@
-use crate::constants::{DEFAULT_MINT_DECIMALS, RELEASE_TIME, TOKEN_VEST_AMOUNT, VESTING_NUMBER};
+use crate::constants::DEFAULT_MINT_DECIMALS;
 use crate::error::DefiOSError;
 use crate::event::RepoTokenChanged;
-use crate::helper::find_metadata_account;
-use crate::state::{Repository, Schedule, VestingSchedule};
+use crate::helper::{
+    build_vesting_schedules, find_metadata_account, get_vesting_allocation, get_vesting_window,
+    validate_vesting_plan,
+};
+use crate::state::{Repository, VestingPlan, VestingSchedule};
 use anchor_lang::prelude::*;
 use anchor_spl::metadata::mpl_token_metadata::types::DataV2;
 use anchor_spl::{
-    associated_token::{create, get_associated_token_address, AssociatedToken, Create},
+    associated_token::{
+        create, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
     metadata::{create_metadata_accounts_v3, CreateMetadataAccountsV3, Metadata},
-    token,
-    token::{Mint, Token},
+    token_interface,
+    token_interface::{Mint, TokenInterface},
 };
 
 #[derive(Accounts)]
@@ -54,15 +59,16 @@ pub struct ChangeRepoToken<'info> {
         payer = repository_creator,
         mint::authority = rewards_mint,
         mint::decimals = DEFAULT_MINT_DECIMALS,
+        mint::token_program = token_program,
         seeds = [b"Miners",
         b"MinerC",
         repository_account.key().as_ref()],
         bump
     )]
-    pub rewards_mint: Option<Account<'info, Mint>>,
-    pub imported_mint: Option<Account<'info, Mint>>,
+    pub rewards_mint: Option<InterfaceAccount<'info, Mint>>,
+    pub imported_mint: Option<InterfaceAccount<'info, Mint>>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_metadata_program: Program<'info, Metadata>,
     pub rent: Sysvar<'info, Rent>,
@@ -73,6 +79,7 @@ pub fn handler(
     token_name: Box<Option<String>>,
     token_symbol: Box<Option<String>>,
     token_metadata_uri: Box<Option<String>>,
+    vesting_plan: Option<VestingPlan>,
 ) -> Result<()> {
     let repository_account = &mut ctx.accounts.repository_account;
     let rewards_mint = &ctx.accounts.rewards_mint;
@@ -95,10 +102,17 @@ pub fn handler(
         DefiOSError::RepoTokenChangeRejected
     );
 
+    let vesting_plan = vesting_plan.unwrap_or_default();
+    require!(
+        validate_vesting_plan(&vesting_plan),
+        DefiOSError::InvalidVestingPlan
+    );
+
     let repository_account_key = repository_account.key();
     let mut rewards_mint_key: Option<Pubkey> = None;
     let mut vesting_schedule_key: Option<Pubkey> = None;
     let mut token_imported: bool = false;
+    let mut vesting_allocation: u64 = 0;
     if let (
         Some(rewards_mint),
         Some(vesting_account),
@@ -182,10 +196,16 @@ pub fn handler(
             ))?;
         }
         // Add checks to ensure token vesting accounts are correct
-        let expected_vesting_token_account =
-            get_associated_token_address(&vesting_account.key(), &rewards_mint.key());
-        let expected_repository_token_pool_account =
-            get_associated_token_address(&repository_creator.key(), &rewards_mint.key());
+        let expected_vesting_token_account = get_associated_token_address_with_program_id(
+            &vesting_account.key(),
+            &rewards_mint.key(),
+            &token_program.key(),
+        );
+        let expected_repository_token_pool_account = get_associated_token_address_with_program_id(
+            &repository_creator.key(),
+            &rewards_mint.key(),
+            &token_program.key(),
+        );
         require!(
             expected_vesting_token_account.eq(&vesting_token_account.key())
                 && expected_repository_token_pool_account
@@ -193,6 +213,15 @@ pub fn handler(
             DefiOSError::TokenAccountMismatch
         );
 
+        // Build vesting tranches from the requested plan
+        let start_time = u64::from_ne_bytes(Clock::get()?.unix_timestamp.to_ne_bytes());
+        let schedules = build_vesting_schedules(&vesting_plan, start_time, rewards_mint.decimals)
+            .ok_or(DefiOSError::MathOverflow)?;
+        let (vesting_start, vesting_end) =
+            get_vesting_window(&vesting_plan, start_time).ok_or(DefiOSError::MathOverflow)?;
+        vesting_allocation = get_vesting_allocation(&vesting_plan, rewards_mint.decimals)
+            .ok_or(DefiOSError::MathOverflow)?;
+
         let bump = ctx.bumps.rewards_mint;
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"Miners",
@@ -201,17 +230,17 @@ pub fn handler(
             &[bump],
         ]];
 
-        token::mint_to(
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                token::MintTo {
+                token_interface::MintTo {
                     mint: rewards_mint.to_account_info(),
                     to: vesting_token_account.to_account_info(),
                     authority: rewards_mint.to_account_info(),
                 },
                 signer_seeds,
             ),
-            VESTING_NUMBER * TOKEN_VEST_AMOUNT * u64::pow(10, rewards_mint.decimals.into()),
+            vesting_allocation,
         )?;
 
         // On-chain token metadata for the mint
@@ -245,17 +274,12 @@ pub fn handler(
         vesting_account.bump = ctx.bumps.vesting_account;
         vesting_account.destination_address = repository_creator_token_account.key();
         vesting_account.mint_address = rewards_mint.key();
-        vesting_account.schedules = vec![];
-
-        // Add schedules to vesting
-        let mut release_time = u64::from_ne_bytes(Clock::get()?.unix_timestamp.to_ne_bytes());
-        for _i in 0..VESTING_NUMBER {
-            vesting_account.schedules.push(Schedule {
-                release_time,
-                amount: TOKEN_VEST_AMOUNT * u64::pow(10, rewards_mint.decimals.into()),
-            });
-            release_time += RELEASE_TIME;
-        }
+        vesting_account.schedules = schedules;
+        vesting_account.mode = vesting_plan.mode;
+        vesting_account.start_time = vesting_start;
+        vesting_account.end_time = vesting_end;
+        vesting_account.total_amount = vesting_allocation;
+        vesting_account.released_amount = 0;
     } else {
         if let Some(imported_mint) = imported_mint {
             rewards_mint_key = Some(imported_mint.key());
@@ -264,6 +288,7 @@ pub fn handler(
     };
 
     repository_account.vesting_schedule = vesting_schedule_key;
+    repository_account.vesting_allocation = vesting_allocation;
     repository_account.repo_token = rewards_mint_key.unwrap();
 
     emit!(RepoTokenChanged {
@@ -0,0 +1,97 @@
+use crate::error::DefiOSError;
+use crate::event::VestingBeneficiaryChanged;
+use crate::state::{Repository, VestingSchedule};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{
+        create, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_interface::{Mint, TokenInterface},
+};
+
+#[derive(Accounts)]
+pub struct ChangeVestingBeneficiary<'info> {
+    #[account(
+        mut,
+        address = repository_account.repository_creator @ DefiOSError::UnauthorizedUser,
+    )]
+    pub repository_creator: Signer<'info>,
+    #[account(
+        seeds = [
+            b"repository",
+            repository_account.id.as_bytes(),
+            repository_creator.key().as_ref(),
+        ],
+        bump = repository_account.bump
+    )]
+    pub repository_account: Box<Account<'info, Repository>>,
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            repository_account.key().as_ref(),
+        ],
+        constraint = vesting_account.mint_address == repository_account.repo_token,
+        bump = vesting_account.bump
+    )]
+    pub vesting_account: Account<'info, VestingSchedule>,
+    #[account(address = vesting_account.mint_address)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub new_beneficiary: SystemAccount<'info>,
+    ///CHECK: The account checks are done in function, unchecked as it might not exist and will be created in that case
+    #[account(mut)]
+    pub new_beneficiary_token_account: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ChangeVestingBeneficiary>) -> Result<()> {
+    let repository_creator = &ctx.accounts.repository_creator;
+    let repository_account = &ctx.accounts.repository_account;
+    let vesting_account = &mut ctx.accounts.vesting_account;
+    let token_mint = &ctx.accounts.token_mint;
+    let new_beneficiary = &ctx.accounts.new_beneficiary;
+    let new_beneficiary_token_account = &ctx.accounts.new_beneficiary_token_account;
+    let token_program = &ctx.accounts.token_program;
+    let associated_token_program = &ctx.accounts.associated_token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    // Create beneficiary token account if empty
+    if new_beneficiary_token_account.data_is_empty() {
+        create(CpiContext::new(
+            associated_token_program.to_account_info(),
+            Create {
+                payer: repository_creator.to_account_info(),
+                associated_token: new_beneficiary_token_account.to_account_info(),
+                authority: new_beneficiary.to_account_info(),
+                mint: token_mint.to_account_info(),
+                system_program: system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+    }
+
+    let expected_new_beneficiary_token_account = get_associated_token_address_with_program_id(
+        &new_beneficiary.key(),
+        &token_mint.key(),
+        &token_program.key(),
+    );
+    require!(
+        expected_new_beneficiary_token_account.eq(&new_beneficiary_token_account.key()),
+        DefiOSError::TokenAccountMismatch
+    );
+
+    let old_destination = vesting_account.destination_address;
+    vesting_account.destination_address = new_beneficiary_token_account.key();
+
+    emit!(VestingBeneficiaryChanged {
+        repository: repository_account.key(),
+        vesting_account: vesting_account.key(),
+        old_destination: old_destination,
+        new_destination: new_beneficiary_token_account.key(),
+        new_beneficiary: new_beneficiary.key()
+    });
+
+    Ok(())
+}
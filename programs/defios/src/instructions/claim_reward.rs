@@ -1,26 +1,24 @@
 use crate::{
     error::DefiOSError,
-    event::RewardClaimed,
+    event::{BountyRewardClaimed, RewardClaimed},
+    helper::{get_amount_after_transfer_fee, get_reward_cuts, get_reward_share},
     state::{Issue, PullRequest, Repository},
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::{
-        create as create_associated_token_account, get_associated_token_address, AssociatedToken,
-        Create,
+        create as create_associated_token_account, get_associated_token_address_with_program_id,
+        AssociatedToken, Create,
     },
-    token::{transfer, Mint, Token, TokenAccount, Transfer},
+    token_2022,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 #[derive(Accounts)]
 pub struct ClaimReward<'info> {
-    #[account(
-        mut,
-        constraint = pull_request_creator.key().eq(&pull_request.sent_by) @ DefiOSError::UnauthorizedUser,
-    )]
+    #[account(mut)]
     pub pull_request_creator: Signer<'info>,
     #[account(
-        mut,
         seeds = [
             b"pullrequestadded",
             issue_account.key().as_ref(),
@@ -28,15 +26,20 @@ pub struct ClaimReward<'info> {
         ],
         bump = pull_request.bump
     )]
-    pub pull_request: Account<'info, PullRequest>,
+    pub pull_request: Option<Account<'info, PullRequest>>,
     /// CHECK: PDA check is done at the handler function
     #[account(mut)]
     pub pull_request_creator_reward_account: UncheckedAccount<'info>,
+    /// CHECK: PDA check is done at the handler function
+    #[account(mut)]
+    pub repository_creator_reward_account: UncheckedAccount<'info>,
+    /// CHECK: PDA check is done at the handler function
+    #[account(mut)]
+    pub issue_creator_reward_account: UncheckedAccount<'info>,
     #[account(
-        constraint = rewards_mint.key() == issue_account.issue_token,
-        constraint = rewards_mint.key() == issue_token_pool_account.mint
+        constraint = rewards_mint.key() == issue_account.issue_token
     )]
-    pub rewards_mint: Account<'info, Mint>,
+    pub rewards_mint: InterfaceAccount<'info, Mint>,
     #[account(
         address = repository_account.repository_creator
     )]
@@ -71,60 +74,68 @@ pub struct ClaimReward<'info> {
 
     #[account(
         mut,
-        constraint = issue_token_pool_account.owner == issue_account.key()
+        constraint = issue_token_pool_account.owner == issue_account.key(),
+        constraint = issue_token_pool_account.mint == rewards_mint.key()
     )]
-    pub issue_token_pool_account: Box<Account<'info, TokenAccount>>,
+    pub issue_token_pool_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn handler(ctx: Context<ClaimReward>) -> Result<()> {
-    let pull_request_creator = &mut ctx.accounts.pull_request_creator;
-    let rewards_mint = &ctx.accounts.rewards_mint;
-    let repository_account = &ctx.accounts.repository_account;
-    let issue_account = &mut ctx.accounts.issue_account;
-    let pull_request_creator_reward_account = &mut ctx.accounts.pull_request_creator_reward_account;
-    let issue_token_pool_account = &mut ctx.accounts.issue_token_pool_account;
-    let associated_token_program = &ctx.accounts.associated_token_program;
-    let system_program = &ctx.accounts.system_program;
-    let token_program = &ctx.accounts.token_program;
-    let pull_request = &mut ctx.accounts.pull_request;
-
-    //Creating token account if empty
-    if pull_request_creator_reward_account.data_is_empty() {
-        create_associated_token_account(CpiContext::new(
-            associated_token_program.to_account_info(),
-            Create {
-                payer: pull_request_creator.to_account_info(),
-                associated_token: pull_request_creator_reward_account.to_account_info(),
-                authority: pull_request_creator.to_account_info(),
-                mint: rewards_mint.to_account_info(),
-                system_program: system_program.to_account_info(),
-                token_program: token_program.to_account_info(),
-            },
-        ))?;
-    }
-
-    //checking if issue token account sent is same as expected
-    let expected_pull_request_creator_reward_account =
-        get_associated_token_address(&pull_request_creator.key(), &rewards_mint.key());
+//remaining accounts hold (bounty mint, bounty pool, claimer reward account, maintainer reward
+//account, issue reporter reward account, token program) for every bounty pool on the issue,
+//in the order they were added
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimReward<'info>>) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let pull_request_creator = &accounts.pull_request_creator;
+    let rewards_mint = &accounts.rewards_mint;
+    let repository_account = &accounts.repository_account;
+    let issue_account = &accounts.issue_account;
+    let token_program = &accounts.token_program;
+    let pull_request = accounts
+        .pull_request
+        .as_ref()
+        .map(|pull_request| pull_request.key());
+    let bounty_accounts = ctx.remaining_accounts;
 
+    //require a reward split to be accepted to claim rewards
     require!(
-        expected_pull_request_creator_reward_account.eq(&pull_request_creator_reward_account.key()),
-        DefiOSError::TokenAccountMismatch
+        !issue_account.reward_shares.is_empty(),
+        DefiOSError::PullRequestNotYetAccepted
     );
-
-    //require pull request to be accepted to claim rewards
+    //each recipient named in the split claims their own share once
+    let share_index = issue_account
+        .reward_shares
+        .iter()
+        .position(|share| share.recipient == pull_request_creator.key())
+        .ok_or(DefiOSError::NoRewardShare)?;
     require!(
-        pull_request.accepted,
-        DefiOSError::PullRequestNotYetAccepted
+        !issue_account.reward_shares[share_index].claimed,
+        DefiOSError::RewardAlreadyClaimed
+    );
+    let share_bps = issue_account.reward_shares[share_index].share_bps;
+    let unclaimed_bps: u64 = issue_account
+        .reward_shares
+        .iter()
+        .filter(|share| !share.claimed)
+        .map(|share| share.share_bps as u64)
+        .sum();
+    require!(
+        bounty_accounts.len() == issue_account.bounty_pools.len() * 6,
+        DefiOSError::MissingBountyAccounts
+    );
+    //a recipient can't skip the staked issue tokens and still have their share marked claimed
+    require!(
+        issue_account.total_stake_amount == 0 || accounts.issue_token_pool_account.is_some(),
+        DefiOSError::MissingIssueTokenPool
     );
 
-    // Transferring pool balance to commit creator
+    // Transferring pool balances to commit creator
     let issue_index_str = issue_account.index.to_string();
     let repository_account_key = repository_account.key();
     let issue_creator_key = issue_account.issue_creator.key();
+    let issue_account_key = issue_account.key();
 
     let signer_seeds: &[&[&[u8]]] = &[&[
         b"issue",
@@ -133,30 +144,221 @@ pub fn handler(ctx: Context<ClaimReward>) -> Result<()> {
         issue_creator_key.as_ref(),
         &[issue_account.bump],
     ]];
-
-    let issue_token_balance = issue_token_pool_account.amount;
-    require!(issue_token_balance > 0, DefiOSError::NoMoneyStakedOnIssue);
-
-    if issue_token_balance > 0 {
-        transfer(
-            CpiContext::new_with_signer(
-                token_program.to_account_info(),
-                Transfer {
-                    from: issue_token_pool_account.to_account_info(),
-                    to: pull_request_creator_reward_account.to_account_info(),
-                    authority: issue_account.to_account_info(),
-                },
+    if let Some(issue_token_pool_account) = &accounts.issue_token_pool_account {
+        let issue_token_balance =
+            get_reward_share(issue_token_pool_account.amount, share_bps, unclaimed_bps)
+                .ok_or(DefiOSError::MathOverflow)?;
+        if issue_token_balance > 0 {
+            let (reward_amount, maintainer_amount, issue_reporter_amount) = pay_split_reward(
+                accounts,
+                &rewards_mint.to_account_info(),
+                &issue_token_pool_account.to_account_info(),
+                [
+                    &accounts
+                        .pull_request_creator_reward_account
+                        .to_account_info(),
+                    &accounts.repository_creator_reward_account.to_account_info(),
+                    &accounts.issue_creator_reward_account.to_account_info(),
+                ],
+                &token_program.to_account_info(),
                 signer_seeds,
-            ),
-            issue_token_balance,
+                issue_token_balance,
+                rewards_mint.decimals,
+            )?;
+
+            emit!(RewardClaimed {
+                reward_claimmee: pull_request_creator.key(),
+                reward_amount: reward_amount,
+                pull_request: pull_request,
+                share_bps: share_bps,
+                maintainer: accounts.repository_creator.key(),
+                maintainer_amount: maintainer_amount,
+                issue_reporter: accounts.issue_creator.key(),
+                issue_reporter_amount: issue_reporter_amount
+            });
+        }
+    }
+
+    for (bounty_pool, bounty_group) in issue_account
+        .bounty_pools
+        .iter()
+        .zip(bounty_accounts.chunks(6))
+    {
+        let (bounty_mint, bounty_pool_account, bounty_token_program) =
+            (&bounty_group[0], &bounty_group[1], &bounty_group[5]);
+
+        require!(
+            bounty_mint.key() == bounty_pool.mint,
+            DefiOSError::MintNotAllowedOnIssue
+        );
+        require!(
+            bounty_mint.owner.eq(&bounty_token_program.key())
+                && (bounty_token_program.key() == anchor_spl::token::ID
+                    || bounty_token_program.key() == token_2022::ID),
+            DefiOSError::TokenAccountMismatch
+        );
+        require!(
+            bounty_pool_account
+                .key()
+                .eq(&get_associated_token_address_with_program_id(
+                    &issue_account_key,
+                    &bounty_mint.key(),
+                    &bounty_token_program.key(),
+                )),
+            DefiOSError::TokenAccountMismatch
+        );
+
+        //pools nobody staked into were never created
+        if bounty_pool_account.data_is_empty() {
+            continue;
+        }
+        let bounty_balance = get_reward_share(
+            InterfaceAccount::<TokenAccount>::try_from(bounty_pool_account)?.amount,
+            share_bps,
+            unclaimed_bps,
+        )
+        .ok_or(DefiOSError::MathOverflow)?;
+        if bounty_balance == 0 {
+            continue;
+        }
+        let bounty_decimals = InterfaceAccount::<Mint>::try_from(bounty_mint)?.decimals;
+
+        let (reward_amount, maintainer_amount, issue_reporter_amount) = pay_split_reward(
+            accounts,
+            bounty_mint,
+            bounty_pool_account,
+            [&bounty_group[2], &bounty_group[3], &bounty_group[4]],
+            bounty_token_program,
+            signer_seeds,
+            bounty_balance,
+            bounty_decimals,
         )?;
-    };
 
-    emit!(RewardClaimed {
-        reward_claimmee: pull_request_creator.key(),
-        reward_amount: issue_token_balance,
-        pull_request: pull_request.key()
-    });
+        emit!(BountyRewardClaimed {
+            reward_claimmee: pull_request_creator.key(),
+            bounty_mint: bounty_mint.key(),
+            reward_amount: reward_amount,
+            pull_request: pull_request,
+            maintainer_amount: maintainer_amount,
+            issue_reporter_amount: issue_reporter_amount
+        });
+    }
+
+    //a share with nothing left to pay out is still settled
+    ctx.accounts.issue_account.reward_shares[share_index].claimed = true;
 
     Ok(())
 }
+
+//pays one pool's reward after taking the repository's maintainer and issue reporter cuts,
+//returns what the (contributor, maintainer, issue reporter) received after transfer fees
+fn pay_split_reward<'info>(
+    accounts: &ClaimReward<'info>,
+    mint: &AccountInfo<'info>,
+    pool_account: &AccountInfo<'info>,
+    reward_accounts: [&AccountInfo<'info>; 3],
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+    decimals: u8,
+) -> Result<(u64, u64, u64)> {
+    let issue_account = &accounts.issue_account;
+    let (contributor_amount, maintainer_amount, issue_reporter_amount) = get_reward_cuts(
+        amount,
+        issue_account.maintainer_cut_bps,
+        issue_account.issue_reporter_cut_bps,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+
+    let recipients = [
+        (
+            accounts.pull_request_creator.to_account_info(),
+            contributor_amount,
+        ),
+        (
+            accounts.repository_creator.to_account_info(),
+            maintainer_amount,
+        ),
+        (
+            accounts.issue_creator.to_account_info(),
+            issue_reporter_amount,
+        ),
+    ];
+    let epoch = Clock::get()?.epoch;
+    let mut received = [0u64; 3];
+
+    for (index, (owner, amount)) in recipients.iter().enumerate() {
+        if *amount == 0 {
+            continue;
+        }
+        pay_reward(
+            accounts,
+            owner,
+            reward_accounts[index],
+            mint,
+            pool_account,
+            token_program,
+            signer_seeds,
+            *amount,
+            decimals,
+        )?;
+        received[index] =
+            get_amount_after_transfer_fee(mint, epoch, *amount).ok_or(DefiOSError::MathOverflow)?;
+    }
+
+    Ok((received[0], received[1], received[2]))
+}
+
+//creates the recipient's token account if needed and moves part of a pool into it
+fn pay_reward<'info>(
+    accounts: &ClaimReward<'info>,
+    owner: &AccountInfo<'info>,
+    reward_account: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    pool_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    //Creating token account if empty, the claimer pays for it
+    if reward_account.data_is_empty() {
+        create_associated_token_account(CpiContext::new(
+            accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: accounts.pull_request_creator.to_account_info(),
+                associated_token: reward_account.to_account_info(),
+                authority: owner.to_account_info(),
+                mint: mint.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+    }
+
+    //checking if reward token account sent is same as expected
+    let expected_reward_account = get_associated_token_address_with_program_id(
+        &owner.key(),
+        &mint.key(),
+        &token_program.key(),
+    );
+    require!(
+        expected_reward_account.eq(&reward_account.key()),
+        DefiOSError::TokenAccountMismatch
+    );
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: pool_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: reward_account.to_account_info(),
+                authority: accounts.issue_account.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        decimals,
+    )
+}
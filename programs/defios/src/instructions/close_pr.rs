@@ -0,0 +1,45 @@
+use crate::error::DefiOSError;
+use crate::event::PullRequestClosed;
+use crate::state::{Issue, PullRequest};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ClosePullRequest<'info> {
+    pub initiator: Signer<'info>,
+    ///CHECK: rent goes back to the author the pull request account was derived from
+    #[account(mut)]
+    pub pull_request_addr: UncheckedAccount<'info>,
+    pub issue: Account<'info, Issue>,
+    #[account(
+        mut,
+        close = pull_request_addr,
+        seeds = [
+            b"pullrequestadded",
+            issue.key().as_ref(),
+            pull_request_addr.key().as_ref()
+        ],
+        bump = pull_request_metadata_account.bump
+    )]
+    pub pull_request_metadata_account: Account<'info, PullRequest>,
+}
+
+pub fn handler(ctx: Context<ClosePullRequest>) -> Result<()> {
+    let pull_request_addr = &ctx.accounts.pull_request_addr;
+    let issue = &ctx.accounts.issue;
+    let pull_request_metadata_account = &ctx.accounts.pull_request_metadata_account;
+
+    require!(issue.closed_at.is_some(), DefiOSError::IssueNotClosed);
+    //accepted pull requests are still needed to claim rewards
+    require!(
+        !pull_request_metadata_account.accepted,
+        DefiOSError::PullRequestAlreadyAccepted
+    );
+
+    emit!(PullRequestClosed {
+        sent_by: pull_request_addr.key(),
+        issue: issue.key(),
+        pull_request: pull_request_metadata_account.key()
+    });
+
+    Ok(())
+}
@@ -1,18 +1,21 @@
-use crate::constants::AUTHORIZED_PUBLIC_KEY;
 use crate::error::DefiOSError;
-use crate::state::CommunalAccount;
+use crate::state::{CommunalAccount, ProgramConfig, Repository, Treasury};
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::{create, get_associated_token_address, AssociatedToken, Create},
-    mint::USDC,
-    token::{Mint, Token},
+    associated_token::{
+        create, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_interface::{Mint, TokenInterface},
 };
 
 #[derive(Accounts)]
 pub struct RegisterCommunalAccount<'info> {
-    ///CHECK: Authority can only have specified public key
-    #[account(mut, signer)]
-    //constraint=AUTHORIZED_PUBLIC_KEY.eq(&authority.key())@DefiOSError::UnauthorizedActionAttempted)]
+    ///CHECK: Authority has to be the admin set in the program config
+    #[account(
+        mut,
+        signer,
+        address = program_config.admin @ DefiOSError::UnauthorizedActionAttempted
+    )]
     pub authority: AccountInfo<'info>,
     #[account(init_if_needed,
         payer = authority,
@@ -32,10 +35,44 @@ pub struct RegisterCommunalAccount<'info> {
     ///CHECK: This is handled in function body
     #[account(mut)]
     pub communal_usdc_account: UncheckedAccount<'info>,
-    pub rewards_mint: Account<'info, Mint>,
-    //#[account(address=USDC)]
-    pub usdc_mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
+    pub rewards_mint: InterfaceAccount<'info, Mint>,
+    #[account(address = program_config.quote_mint @ DefiOSError::InvalidQuoteMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(constraint = repository_account.repo_token == rewards_mint.key())]
+    pub repository_account: Box<Account<'info, Repository>>,
+    #[account(init_if_needed,
+        payer = authority,
+        space = 8+Treasury::INIT_SPACE,
+        seeds = [
+            b"treasury",
+            repository_account.key().as_ref()
+        ],
+    bump
+    )]
+    pub repository_treasury: Box<Account<'info, Treasury>>,
+    ///CHECK: This is handled in function body
+    #[account(mut)]
+    pub repository_treasury_usdc_account: UncheckedAccount<'info>,
+    #[account(init_if_needed,
+        payer = authority,
+        space = 8+Treasury::INIT_SPACE,
+        seeds = [
+            b"treasury",
+            b"protocol"
+        ],
+    bump
+    )]
+    pub protocol_treasury: Box<Account<'info, Treasury>>,
+    ///CHECK: This is handled in function body
+    #[account(mut)]
+    pub protocol_treasury_usdc_account: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub usdc_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -46,11 +83,21 @@ pub fn handler(ctx: Context<RegisterCommunalAccount>) -> Result<()> {
     let communal_deposit = &mut ctx.accounts.communal_deposit;
     let communal_token_account = &mut ctx.accounts.communal_token_account;
     let token_program = &ctx.accounts.token_program;
+    let usdc_token_program = &ctx.accounts.usdc_token_program;
     let associated_token_program = &ctx.accounts.associated_token_program;
     let rewards_mint = &ctx.accounts.rewards_mint;
     let communal_usdc_account = &ctx.accounts.communal_usdc_account;
     let usdc_mint = &ctx.accounts.usdc_mint;
+    let repository_account = &ctx.accounts.repository_account;
+    let repository_treasury = &mut ctx.accounts.repository_treasury;
+    let repository_treasury_usdc_account = &ctx.accounts.repository_treasury_usdc_account;
+    let protocol_treasury = &mut ctx.accounts.protocol_treasury;
+    let protocol_treasury_usdc_account = &ctx.accounts.protocol_treasury_usdc_account;
     communal_deposit.bump = ctx.bumps.communal_deposit;
+    repository_treasury.bump = ctx.bumps.repository_treasury;
+    repository_treasury.repository = Some(repository_account.key());
+    protocol_treasury.bump = ctx.bumps.protocol_treasury;
+    protocol_treasury.repository = None;
     //creates communal token account for new spl token
     if communal_token_account.data_is_empty() {
         create(CpiContext::new(
@@ -66,8 +113,11 @@ pub fn handler(ctx: Context<RegisterCommunalAccount>) -> Result<()> {
         ))?;
     }
 
-    let expected_communal_token_account =
-        get_associated_token_address(&communal_deposit.key(), &rewards_mint.key());
+    let expected_communal_token_account = get_associated_token_address_with_program_id(
+        &communal_deposit.key(),
+        &rewards_mint.key(),
+        &token_program.key(),
+    );
     require!(
         expected_communal_token_account.eq(&communal_token_account.key()),
         DefiOSError::TokenAccountMismatch
@@ -83,17 +133,65 @@ pub fn handler(ctx: Context<RegisterCommunalAccount>) -> Result<()> {
                 authority: communal_deposit.to_account_info(),
                 mint: usdc_mint.to_account_info(),
                 system_program: system_program.to_account_info(),
-                token_program: token_program.to_account_info(),
+                token_program: usdc_token_program.to_account_info(),
             },
         ))?;
     }
 
-    let expected_communal_usdc_account =
-        get_associated_token_address(&communal_deposit.key(), &usdc_mint.key());
+    let expected_communal_usdc_account = get_associated_token_address_with_program_id(
+        &communal_deposit.key(),
+        &usdc_mint.key(),
+        &usdc_token_program.key(),
+    );
     require!(
         expected_communal_usdc_account.eq(&communal_usdc_account.key()),
         DefiOSError::TokenAccountMismatch
     );
 
+    //creates treasury usdc accounts that trading fees are paid into
+    if repository_treasury_usdc_account.data_is_empty() {
+        create(CpiContext::new(
+            associated_token_program.to_account_info(),
+            Create {
+                payer: authority.to_account_info(),
+                associated_token: repository_treasury_usdc_account.to_account_info(),
+                authority: repository_treasury.to_account_info(),
+                mint: usdc_mint.to_account_info(),
+                system_program: system_program.to_account_info(),
+                token_program: usdc_token_program.to_account_info(),
+            },
+        ))?;
+    }
+
+    if protocol_treasury_usdc_account.data_is_empty() {
+        create(CpiContext::new(
+            associated_token_program.to_account_info(),
+            Create {
+                payer: authority.to_account_info(),
+                associated_token: protocol_treasury_usdc_account.to_account_info(),
+                authority: protocol_treasury.to_account_info(),
+                mint: usdc_mint.to_account_info(),
+                system_program: system_program.to_account_info(),
+                token_program: usdc_token_program.to_account_info(),
+            },
+        ))?;
+    }
+
+    let expected_repository_treasury_usdc_account = get_associated_token_address_with_program_id(
+        &repository_treasury.key(),
+        &usdc_mint.key(),
+        &usdc_token_program.key(),
+    );
+    let expected_protocol_treasury_usdc_account = get_associated_token_address_with_program_id(
+        &protocol_treasury.key(),
+        &usdc_mint.key(),
+        &usdc_token_program.key(),
+    );
+    require!(
+        expected_repository_treasury_usdc_account.eq(&repository_treasury_usdc_account.key())
+            && expected_protocol_treasury_usdc_account.eq(&protocol_treasury_usdc_account.key()),
+        DefiOSError::TokenAccountMismatch
+    );
+
     Ok(())
 }
@@ -1,17 +1,23 @@
-use crate::helper::find_metadata_account;
+use crate::helper::{
+    build_vesting_schedules, find_metadata_account, get_vesting_allocation, get_vesting_window,
+    validate_vesting_plan,
+};
 use crate::{
-    constants::{DEFAULT_MINT_DECIMALS, RELEASE_TIME, TOKEN_VEST_AMOUNT, VESTING_NUMBER},
+    constants::{DEFAULT_MINT_DECIMALS, MAX_TRADE_FEE_BPS},
+    curve::validate_curve,
     error::DefiOSError,
     event::RepositoryCreated,
-    state::{Repository, Schedule, VerifiedUser, VestingSchedule},
+    state::{CurveConfig, ProgramConfig, Repository, VerifiedUser, VestingPlan, VestingSchedule},
 };
 use anchor_lang::prelude::*;
 use anchor_spl::metadata::mpl_token_metadata::types::DataV2;
 use anchor_spl::{
-    associated_token::{create, get_associated_token_address, AssociatedToken, Create},
+    associated_token::{
+        create, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
     metadata::{create_metadata_accounts_v3, CreateMetadataAccountsV3, Metadata},
-    token,
-    token::{Mint, Token},
+    token_interface,
+    token_interface::{Mint, TokenInterface},
 };
 
 #[derive(Accounts)]
@@ -70,14 +76,21 @@ pub struct CreateRepository<'info> {
         payer = repository_creator,
         mint::authority = rewards_mint,
         mint::decimals = DEFAULT_MINT_DECIMALS,
+        mint::token_program = token_program,
         seeds = [b"Miners",
         b"MinerC",
         repository_account.key().as_ref()],
         bump
     )]
-    pub rewards_mint: Option<Account<'info, Mint>>,
-    pub imported_mint: Option<Account<'info, Mint>>,
-    pub token_program: Program<'info, Token>,
+    pub rewards_mint: Option<InterfaceAccount<'info, Mint>>,
+    pub imported_mint: Option<InterfaceAccount<'info, Mint>>,
+    //governance defaults come from the program config once it has been initialised
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Option<Box<Account<'info, ProgramConfig>>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub token_metadata_program: Program<'info, Metadata>,
@@ -92,6 +105,9 @@ pub fn handler(
     token_name: Box<Option<String>>,
     token_symbol: Box<Option<String>>,
     token_metadata_uri: Box<Option<String>>,
+    curve_config: Option<CurveConfig>,
+    trade_fee_bps: Option<u16>,
+    vesting_plan: Option<VestingPlan>,
 ) -> Result<()> {
     let repository_account = &mut ctx.accounts.repository_account;
     let repository_verified_user = &ctx.accounts.repository_verified_user;
@@ -107,6 +123,22 @@ pub fn handler(
     let imported_mint = &ctx.accounts.imported_mint;
     let rent = &ctx.accounts.rent;
 
+    let curve_config = curve_config.unwrap_or_default();
+    require!(
+        validate_curve(&curve_config),
+        DefiOSError::InvalidCurveConfig
+    );
+    let trade_fee_bps = trade_fee_bps.unwrap_or(0);
+    require!(
+        trade_fee_bps <= MAX_TRADE_FEE_BPS,
+        DefiOSError::TradeFeeTooHigh
+    );
+    let vesting_plan = vesting_plan.unwrap_or_default();
+    require!(
+        validate_vesting_plan(&vesting_plan),
+        DefiOSError::InvalidVestingPlan
+    );
+
     //fills repository account data
     repository_account.bump = ctx.bumps.repository_account;
     repository_account.repository_creator = repository_verified_user.user_pubkey.key();
@@ -114,11 +146,20 @@ pub fn handler(
     repository_account.description = description;
     repository_account.uri = uri;
     repository_account.issue_index = 0;
+    repository_account.curve_config = curve_config;
+    repository_account.trade_fee_bps = trade_fee_bps;
+    repository_account.governance_config = ctx
+        .accounts
+        .program_config
+        .as_ref()
+        .map(|program_config| program_config.default_governance_config)
+        .unwrap_or_default();
 
     let repository_account_key = repository_account.key();
     let mut rewards_mint_key: Option<Pubkey> = None;
     let mut vesting_schedule_key: Option<Pubkey> = None;
     let mut token_imported: bool = false;
+    let mut vesting_allocation: u64 = 0;
     if let (
         Some(rewards_mint),
         Some(vesting_account),
@@ -202,10 +243,16 @@ pub fn handler(
             ))?;
         }
         // Add checks to ensure token vesting accounts are correct
-        let expected_vesting_token_account =
-            get_associated_token_address(&vesting_account.key(), &rewards_mint.key());
-        let expected_repository_token_pool_account =
-            get_associated_token_address(&repository_creator.key(), &rewards_mint.key());
+        let expected_vesting_token_account = get_associated_token_address_with_program_id(
+            &vesting_account.key(),
+            &rewards_mint.key(),
+            &token_program.key(),
+        );
+        let expected_repository_token_pool_account = get_associated_token_address_with_program_id(
+            &repository_creator.key(),
+            &rewards_mint.key(),
+            &token_program.key(),
+        );
         require!(
             expected_vesting_token_account.eq(&vesting_token_account.key())
                 && expected_repository_token_pool_account
@@ -213,6 +260,15 @@ pub fn handler(
             DefiOSError::TokenAccountMismatch
         );
 
+        // Build vesting tranches from the requested plan
+        let start_time = u64::from_ne_bytes(Clock::get()?.unix_timestamp.to_ne_bytes());
+        let schedules = build_vesting_schedules(&vesting_plan, start_time, rewards_mint.decimals)
+            .ok_or(DefiOSError::MathOverflow)?;
+        let (vesting_start, vesting_end) =
+            get_vesting_window(&vesting_plan, start_time).ok_or(DefiOSError::MathOverflow)?;
+        vesting_allocation = get_vesting_allocation(&vesting_plan, rewards_mint.decimals)
+            .ok_or(DefiOSError::MathOverflow)?;
+
         let bump = ctx.bumps.rewards_mint;
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"Miners",
@@ -221,17 +277,17 @@ pub fn handler(
             &[bump],
         ]];
 
-        token::mint_to(
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                token::MintTo {
+                token_interface::MintTo {
                     mint: rewards_mint.to_account_info(),
                     to: vesting_token_account.to_account_info(),
                     authority: rewards_mint.to_account_info(),
                 },
                 signer_seeds,
             ),
-            VESTING_NUMBER * TOKEN_VEST_AMOUNT * u64::pow(10, rewards_mint.decimals.into()),
+            vesting_allocation,
         )?;
 
         // On-chain token metadata for the mint
@@ -265,17 +321,12 @@ pub fn handler(
         vesting_account.bump = ctx.bumps.vesting_account;
         vesting_account.destination_address = repository_creator_token_account.key();
         vesting_account.mint_address = rewards_mint.key();
-        vesting_account.schedules = vec![];
-
-        // Add schedules to vesting
-        let mut release_time = u64::from_ne_bytes(Clock::get()?.unix_timestamp.to_ne_bytes());
-        for _i in 0..VESTING_NUMBER {
-            vesting_account.schedules.push(Schedule {
-                release_time,
-                amount: TOKEN_VEST_AMOUNT * u64::pow(10, rewards_mint.decimals.into()),
-            });
-            release_time += RELEASE_TIME;
-        }
+        vesting_account.schedules = schedules;
+        vesting_account.mode = vesting_plan.mode;
+        vesting_account.start_time = vesting_start;
+        vesting_account.end_time = vesting_end;
+        vesting_account.total_amount = vesting_allocation;
+        vesting_account.released_amount = 0;
     } else {
         if let Some(imported_mint) = imported_mint {
             rewards_mint_key = Some(imported_mint.key());
@@ -285,6 +336,7 @@ pub fn handler(
 
     //add vesting schedule and repository mint key to repository
     repository_account.vesting_schedule = vesting_schedule_key;
+    repository_account.vesting_allocation = vesting_allocation;
     repository_account.repo_token = rewards_mint_key.unwrap();
 
     repository_account.new_token = !token_imported;
@@ -300,7 +352,10 @@ pub fn handler(
         token_symbol: *token_symbol,
         token_metadata_uri: *token_metadata_uri,
         vesting_account: vesting_schedule_key,
-        token_imported: token_imported
+        token_imported: token_imported,
+        curve_config: curve_config,
+        trade_fee_bps: trade_fee_bps,
+        vesting_allocation: vesting_allocation
     });
     Ok(())
 }
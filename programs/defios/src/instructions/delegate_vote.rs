@@ -0,0 +1,54 @@
+use crate::{
+    error::DefiOSError,
+    event::VoteDelegated,
+    state::{Issue, IssueStaker, VerifiedUser},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct DelegateVote<'info> {
+    pub issue_staker: Signer<'info>,
+    pub issue_account: Account<'info, Issue>,
+    #[account(
+        mut,
+        seeds = [b"issuestaker", issue_account.key().as_ref(), issue_staker.key().as_ref()],
+        bump = issue_staker_account.bump
+    )]
+    pub issue_staker_account: Account<'info, IssueStaker>,
+    #[account(
+        seeds = [
+            delegate_verified_user.user_name.as_bytes(),
+            delegate_verified_user.user_pubkey.as_ref(),
+            delegate_verified_user.name_router.as_ref()
+        ],
+        bump = delegate_verified_user.bump
+    )]
+    pub delegate_verified_user: Account<'info, VerifiedUser>,
+}
+
+pub fn handler(ctx: Context<DelegateVote>) -> Result<()> {
+    let issue_staker = &ctx.accounts.issue_staker;
+    let issue_account = &ctx.accounts.issue_account;
+    let issue_staker_account = &mut ctx.accounts.issue_staker_account;
+    let delegate = ctx.accounts.delegate_verified_user.user_pubkey;
+
+    require!(
+        issue_account.closed_at.is_none(),
+        DefiOSError::IssueClosedAlready
+    );
+    require!(
+        !issue_staker_account.has_voted,
+        DefiOSError::PullRequestVotingClosedAlready
+    );
+    require!(delegate != issue_staker.key(), DefiOSError::InvalidDelegate);
+
+    issue_staker_account.delegate = Some(delegate);
+
+    emit!(VoteDelegated {
+        issue: issue_account.key(),
+        issue_staker: issue_staker.key(),
+        delegate: delegate
+    });
+
+    Ok(())
+}
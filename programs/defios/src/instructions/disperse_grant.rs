@@ -1,10 +1,11 @@
 use crate::error::DefiOSError;
 use crate::event::GrantDispersed;
+use crate::helper::get_amount_after_transfer_fee;
 use crate::state::{Issue, Objective, Repository};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::{create as create_associated_token_account, AssociatedToken, Create},
-    token::{transfer, Mint, Token, TokenAccount, Transfer},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 #[derive(Accounts)]
@@ -33,17 +34,17 @@ pub struct DisperseGrant<'info> {
     #[account(mut)]
     pub issue_token_pool_account: UncheckedAccount<'info>,
     #[account(constraint = token_mint.key() == issue_account.issue_token)]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         constraint = objective_stake_account.owner.eq(&objective.key()),
         constraint = objective_stake_account.amount >= disperse_amount @ DefiOSError::InsufficientStakingFunds,
         constraint = objective_stake_account.mint == token_mint.key()
     )]
-    pub objective_stake_account: Account<'info, TokenAccount>,
+    pub objective_stake_account: InterfaceAccount<'info, TokenAccount>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<DisperseGrant>, disperse_amount: u64) -> Result<()> {
@@ -79,25 +80,33 @@ pub fn handler(ctx: Context<DisperseGrant>, disperse_amount: u64) -> Result<()>
         &[objective.bump],
     ]];
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: objective_stake_account.to_account_info(),
+                mint: token_mint.to_account_info(),
                 to: issue_token_pool_account.to_account_info(),
                 authority: objective.to_account_info(),
             },
             signer_seeds,
         ),
         disperse_amount,
+        token_mint.decimals,
     )?;
+    let grant_amount = get_amount_after_transfer_fee(
+        &token_mint.to_account_info(),
+        Clock::get()?.epoch,
+        disperse_amount,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
 
     objective.total_dispersed_grant += disperse_amount;
 
     emit!(GrantDispersed {
         objective: objective.key(),
         issue: issue_account.key(),
-        grant_amount: disperse_amount
+        grant_amount: grant_amount
     });
 
     Ok(())
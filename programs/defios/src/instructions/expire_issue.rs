@@ -0,0 +1,62 @@
+use crate::{
+    error::DefiOSError,
+    event::IssueExpired,
+    state::{Issue, Repository},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExpireIssue<'info> {
+    pub initiator: Signer<'info>,
+    #[account(
+        mut,
+        address = issue_account.repository,
+        seeds = [
+            b"repository",
+            repository_account.id.as_bytes(),
+            repository_account.repository_creator.key().as_ref(),
+        ],
+        bump = repository_account.bump
+    )]
+    pub repository_account: Box<Account<'info, Repository>>,
+    #[account(
+        mut,
+        seeds = [
+            b"issue",
+            issue_account.index.to_string().as_bytes(),
+            repository_account.key().as_ref(),
+            issue_account.issue_creator.key().as_ref(),
+        ],
+        bump = issue_account.bump
+    )]
+    pub issue_account: Account<'info, Issue>,
+}
+
+pub fn handler(ctx: Context<ExpireIssue>) -> Result<()> {
+    let repository_account = &mut ctx.accounts.repository_account;
+    let issue_account = &mut ctx.accounts.issue_account;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(
+        issue_account.closed_at.is_none(),
+        DefiOSError::IssueClosedAlready
+    );
+    require!(
+        matches!(issue_account.deadline, Some(deadline) if current_time > deadline),
+        DefiOSError::IssueDeadlineNotReached
+    );
+
+    //nobody delivered in time, stakers can now take their full stake back
+    issue_account.closed_at = Some(current_time);
+    issue_account.refund_only = true;
+    repository_account.num_open_issues -= 1;
+
+    emit!(IssueExpired {
+        issue: issue_account.key(),
+        repository: repository_account.key(),
+        expired_at: current_time,
+        total_stake_amount: issue_account.total_stake_amount
+    });
+
+    Ok(())
+}
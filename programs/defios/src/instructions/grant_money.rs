@@ -1,11 +1,11 @@
-use crate::constants::TRUSTED_NAME_ROUTERS;
 use crate::error::DefiOSError;
 use crate::event::GrantProvided;
-use crate::state::{Grantee, Objective, Repository, VerifiedUser};
+use crate::helper::get_amount_after_transfer_fee;
+use crate::state::{Grantee, Objective, ProgramConfig, Repository, VerifiedUser};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::{create as create_associated_token_account, AssociatedToken, Create},
-    token::{transfer, Mint, Token, TokenAccount, Transfer},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 #[derive(Accounts)]
@@ -14,7 +14,7 @@ pub struct GrantMoney<'info> {
     #[account(mut)]
     pub grantee: Signer<'info>,
     #[account(
-        // constraint = TRUSTED_NAME_ROUTERS.contains(&grantee_verified_user.name_router),
+        constraint = program_config.trusted_name_routers.contains(&grantee_verified_user.name_router) @ DefiOSError::UntrustedNameRouter,
         seeds = [
             grantee_verified_user.user_name.as_bytes(),
             grantee.key().as_ref(),
@@ -30,7 +30,7 @@ pub struct GrantMoney<'info> {
     pub objective: Account<'info, Objective>,
     pub repository: Box<Account<'info, Repository>>,
     #[account(constraint = token_mint.key() == repository.repo_token)]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
     #[account(
         init_if_needed,
         payer = grantee,
@@ -52,10 +52,15 @@ pub struct GrantMoney<'info> {
         constraint = grantee_stake_account.amount >= transfer_amount @ DefiOSError::InsufficientStakingFunds,
         constraint = grantee_stake_account.mint == token_mint.key()
     )]
-    pub grantee_stake_account: Account<'info, TokenAccount>,
+    pub grantee_stake_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(
@@ -73,12 +78,20 @@ pub fn handler(
     let system_program = &ctx.accounts.system_program;
     let token_mint = &ctx.accounts.token_mint;
 
+    //only what reaches the objective after any transfer fee counts as granted
+    let grant_amount = get_amount_after_transfer_fee(
+        &token_mint.to_account_info(),
+        Clock::get()?.epoch,
+        transfer_amount,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+
     grantee_account.bump = ctx.bumps.grantee_account;
     grantee_account.grantee = grantee.key();
     grantee_account.objective = objective.key();
-    grantee_account.staked_amount += transfer_amount;
+    grantee_account.staked_amount += grant_amount;
     grantee_account.grant_metadata_uri = grant_metadata_uri.clone();
-    objective.total_grant += transfer_amount;
+    objective.total_grant += grant_amount;
 
     //Creating token account if empty
     if objective_stake_account.data_is_empty() {
@@ -95,21 +108,23 @@ pub fn handler(
         ))?;
     }
 
-    transfer(
+    transfer_checked(
         CpiContext::new(
             token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: grantee_stake_account.to_account_info(),
+                mint: token_mint.to_account_info(),
                 to: objective_stake_account.to_account_info(),
                 authority: grantee.to_account_info(),
             },
         ),
         transfer_amount,
+        token_mint.decimals,
     )?;
 
     emit!(GrantProvided {
         grantee: grantee.key(),
-        grant_amount: transfer_amount,
+        grant_amount: grant_amount,
         objective: objective.key(),
         grant_metadata_uri: grant_metadata_uri
     });
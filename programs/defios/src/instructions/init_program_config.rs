@@ -0,0 +1,72 @@
+use crate::constants::{MAX_TRUSTED_NAME_ROUTERS, VOTING_END};
+use crate::error::DefiOSError;
+use crate::event::ProgramConfigUpdated;
+use crate::helper::validate_governance_config;
+use crate::program::Defios;
+use crate::state::{GovernanceConfig, ProgramConfig};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct InitProgramConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8+ProgramConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+    //fixed for good, communal reserves of every repository are held in this mint
+    pub quote_mint: InterfaceAccount<'info, Mint>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Defios>,
+    //only the upgrade authority can bootstrap the config
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ DefiOSError::UnauthorizedActionAttempted
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<InitProgramConfig>,
+    trusted_name_routers: Vec<Pubkey>,
+    voting_end: Option<i64>,
+    default_governance_config: Option<GovernanceConfig>,
+) -> Result<()> {
+    let admin = &ctx.accounts.admin;
+    let program_config = &mut ctx.accounts.program_config;
+    let quote_mint = &ctx.accounts.quote_mint;
+
+    let voting_end = voting_end.unwrap_or(VOTING_END);
+    require!(voting_end > 0, DefiOSError::InvalidVotingPeriod);
+    require!(
+        trusted_name_routers.len() <= MAX_TRUSTED_NAME_ROUTERS,
+        DefiOSError::TooManyTrustedNameRouters
+    );
+    let default_governance_config = default_governance_config.unwrap_or_default();
+    require!(
+        validate_governance_config(&default_governance_config),
+        DefiOSError::InvalidGovernanceConfig
+    );
+
+    program_config.bump = ctx.bumps.program_config;
+    program_config.admin = admin.key();
+    program_config.quote_mint = quote_mint.key();
+    program_config.trusted_name_routers = trusted_name_routers;
+    program_config.voting_end = voting_end;
+    program_config.default_governance_config = default_governance_config;
+
+    emit!(ProgramConfigUpdated {
+        admin: program_config.admin,
+        quote_mint: program_config.quote_mint,
+        trusted_name_routers: program_config.trusted_name_routers.clone(),
+        voting_end: program_config.voting_end,
+        default_governance_config: program_config.default_governance_config
+    });
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use crate::{
+    curve::validate_curve,
+    error::DefiOSError,
+    event::RepositoryMigrated,
+    helper::get_vesting_allocation,
+    state::{CurveConfig, ProgramConfig, Repository, VestingPlan},
+};
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct MigrateRepository<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    ///CHECK: legacy accounts are too small to deserialize, owner and discriminator are checked in function
+    #[account(mut)]
+    pub repository_account: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+    pub rewards_mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateRepository>) -> Result<()> {
+    let payer = &ctx.accounts.payer;
+    let repository_info = ctx.accounts.repository_account.to_account_info();
+    let program_config = &ctx.accounts.program_config;
+
+    require_keys_eq!(
+        *repository_info.owner,
+        *ctx.program_id,
+        ErrorCode::AccountOwnedByWrongProgram
+    );
+    require!(
+        repository_info.try_borrow_data()?.get(..8) == Some(&Repository::DISCRIMINATOR[..]),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    //grows the account to the current layout, fields added since are zero filled
+    let space = 8 + Repository::INIT_SPACE;
+    if repository_info.data_len() < space {
+        let top_up = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(repository_info.lamports());
+        if top_up > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: payer.to_account_info(),
+                        to: repository_info.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        repository_info.realloc(space, true)?;
+    }
+
+    let mut repository = Repository::try_deserialize(&mut &repository_info.try_borrow_data()?[..])?;
+    //a zeroed curve can only come from an account created before curves were configurable
+    require!(
+        !validate_curve(&repository.curve_config),
+        DefiOSError::RepositoryAlreadyMigrated
+    );
+
+    let rewards_mint = &ctx.accounts.rewards_mint;
+    require_keys_eq!(
+        rewards_mint.key(),
+        repository.repo_token,
+        DefiOSError::MintMismatch
+    );
+
+    //reproduces the pricing, fixed vesting carve-out and majority voting legacy repositories had,
+    //the carve-out was minted in raw units and only for repositories with a vesting schedule
+    repository.curve_config = CurveConfig::default();
+    repository.vesting_allocation = if repository.vesting_schedule.is_some() {
+        get_vesting_allocation(&VestingPlan::default(), rewards_mint.decimals)
+            .ok_or(DefiOSError::MathOverflow)?
+    } else {
+        0
+    };
+    repository.governance_config = program_config.default_governance_config;
+    repository.try_serialize(&mut &mut repository_info.try_borrow_mut_data()?[..])?;
+
+    emit!(RepositoryMigrated {
+        repository: repository_info.key(),
+        curve_config: repository.curve_config,
+        governance_config: repository.governance_config
+    });
+
+    Ok(())
+}
@@ -1,47 +1,105 @@
 pub mod accept_issue_vote;
 pub mod accept_objective;
 pub mod accept_pr;
+pub mod accept_reward_split;
+pub mod add_bounty_mint;
 pub mod add_issue;
 pub mod add_objective_data;
 pub mod add_pr;
 pub mod add_roadmap_data;
 pub mod add_verified_user;
+pub mod audit_reserve;
 pub mod buy_tokens;
+pub mod buy_tokens_with_slippage;
+pub mod cancel_issue;
 pub mod change_repo_token;
+pub mod change_vesting_beneficiary;
+pub mod change_vote;
 pub mod claim_reward;
+pub mod close_pr;
 pub mod create_communal_account;
 pub mod create_name_router;
 pub mod create_objective_proposal;
 pub mod create_repository;
+pub mod delegate_vote;
 pub mod disperse_grant;
+pub mod expire_issue;
 pub mod grant_money;
+pub mod init_program_config;
+pub mod migrate_repository;
+pub mod quote_buy;
+pub mod quote_sell;
+pub mod revoke_vesting;
+pub mod revoke_vote_delegation;
 pub mod sell_tokens;
+pub mod sell_tokens_with_slippage;
+pub mod set_governance_config;
+pub mod set_reward_cuts;
+pub mod set_voting_mode;
 pub mod stake_issue;
+pub mod swap_tokens;
 pub mod unlock_tokens;
 pub mod unstake_issue;
+pub mod update_issue_metadata;
+pub mod update_pr;
+pub mod update_program_config;
+pub mod update_repo_token_metadata;
 pub mod vote_objective;
 pub mod vote_pr;
+pub mod withdraw_pr;
+pub mod withdraw_protocol_fees;
+pub mod withdraw_treasury_fees;
+pub mod withdraw_vote;
 
 pub use accept_issue_vote::*;
 pub use accept_objective::*;
 pub use accept_pr::*;
+pub use accept_reward_split::*;
+pub use add_bounty_mint::*;
 pub use add_issue::*;
 pub use add_objective_data::*;
 pub use add_pr::*;
 pub use add_roadmap_data::*;
 pub use add_verified_user::*;
+pub use audit_reserve::*;
 pub use buy_tokens::*;
+pub use buy_tokens_with_slippage::*;
+pub use cancel_issue::*;
 pub use change_repo_token::*;
+pub use change_vesting_beneficiary::*;
+pub use change_vote::*;
 pub use claim_reward::*;
+pub use close_pr::*;
 pub use create_communal_account::*;
 pub use create_name_router::*;
 pub use create_objective_proposal::*;
 pub use create_repository::*;
+pub use delegate_vote::*;
 pub use disperse_grant::*;
+pub use expire_issue::*;
 pub use grant_money::*;
+pub use init_program_config::*;
+pub use migrate_repository::*;
+pub use quote_buy::*;
+pub use quote_sell::*;
+pub use revoke_vesting::*;
+pub use revoke_vote_delegation::*;
 pub use sell_tokens::*;
+pub use sell_tokens_with_slippage::*;
+pub use set_governance_config::*;
+pub use set_reward_cuts::*;
+pub use set_voting_mode::*;
 pub use stake_issue::*;
+pub use swap_tokens::*;
 pub use unlock_tokens::*;
 pub use unstake_issue::*;
+pub use update_issue_metadata::*;
+pub use update_pr::*;
+pub use update_program_config::*;
+pub use update_repo_token_metadata::*;
 pub use vote_objective::*;
 pub use vote_pr::*;
+pub use withdraw_pr::*;
+pub use withdraw_protocol_fees::*;
+pub use withdraw_treasury_fees::*;
+pub use withdraw_vote::*;
@@ -0,0 +1,49 @@
+use crate::error::DefiOSError;
+use crate::helper::{get_buy_quote, is_whole_token_amount};
+use crate::state::{ProgramConfig, Repository, TradeQuote};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct QuoteTrade<'info> {
+    pub repository_account: Box<Account<'info, Repository>>,
+    #[account(
+        seeds = [
+            b"Miners",
+            b"MinerC",
+            repository_account.key().as_ref()
+        ],
+        bump
+    )]
+    pub rewards_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+    #[account(address = program_config.quote_mint @ DefiOSError::InvalidQuoteMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+}
+
+//read only, the quote reaches the caller through the instruction return data
+pub fn handler(ctx: Context<QuoteTrade>, number_of_tokens: u64) -> Result<TradeQuote> {
+    let rewards_mint = &ctx.accounts.rewards_mint;
+    let repository_account = &ctx.accounts.repository_account;
+
+    require!(
+        is_whole_token_amount(number_of_tokens, rewards_mint.decimals)
+            .ok_or(DefiOSError::MathOverflow)?,
+        DefiOSError::FractionalTokenAmount
+    );
+    let quote = get_buy_quote(
+        repository_account,
+        rewards_mint.supply,
+        rewards_mint.decimals,
+        number_of_tokens,
+        &ctx.accounts.usdc_mint.to_account_info(),
+        Clock::get()?.epoch,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+
+    Ok(quote)
+}
@@ -0,0 +1,28 @@
+use crate::error::DefiOSError;
+use crate::helper::{get_sell_quote, is_whole_token_amount};
+use crate::instructions::quote_buy::QuoteTrade;
+use crate::state::TradeQuote;
+use anchor_lang::prelude::*;
+
+//read only, the quote reaches the caller through the instruction return data
+pub fn handler(ctx: Context<QuoteTrade>, number_of_tokens: u64) -> Result<TradeQuote> {
+    let rewards_mint = &ctx.accounts.rewards_mint;
+    let repository_account = &ctx.accounts.repository_account;
+
+    require!(
+        is_whole_token_amount(number_of_tokens, rewards_mint.decimals)
+            .ok_or(DefiOSError::MathOverflow)?,
+        DefiOSError::FractionalTokenAmount
+    );
+    let quote = get_sell_quote(
+        repository_account,
+        rewards_mint.supply,
+        rewards_mint.decimals,
+        number_of_tokens,
+        &ctx.accounts.usdc_mint.to_account_info(),
+        Clock::get()?.epoch,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+
+    Ok(quote)
+}
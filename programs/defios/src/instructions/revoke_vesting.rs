@@ -0,0 +1,113 @@
+use crate::error::DefiOSError;
+use crate::event::VestingRevoked;
+use crate::helper::calculate_linear_vested;
+use crate::state::{Repository, VestingMode, VestingSchedule};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    #[account(
+        address = repository_account.repository_creator @ DefiOSError::UnauthorizedUser,
+    )]
+    pub repository_creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"repository",
+            repository_account.id.as_bytes(),
+            repository_creator.key().as_ref(),
+        ],
+        bump = repository_account.bump
+    )]
+    pub repository_account: Box<Account<'info, Repository>>,
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            repository_account.key().as_ref(),
+        ],
+        constraint = vesting_account.mint_address == repository_account.repo_token,
+        bump = vesting_account.bump
+    )]
+    pub vesting_account: Account<'info, VestingSchedule>,
+    #[account(
+        mut,
+        constraint = vesting_token_account.mint.eq(&token_mint.key()),
+        constraint = vesting_token_account.owner.eq(&vesting_account.key())
+    )]
+    pub vesting_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = vesting_account.mint_address)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<RevokeVesting>) -> Result<()> {
+    let repository_account = &mut ctx.accounts.repository_account;
+    let vesting_account = &mut ctx.accounts.vesting_account;
+    let vesting_token_account = &ctx.accounts.vesting_token_account;
+    let token_mint = &ctx.accounts.token_mint;
+    let token_program = &ctx.accounts.token_program;
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+
+    //collects everything that has not vested yet, already vested tokens stay unlockable
+    let mut revoked_amount = 0;
+    match vesting_account.mode {
+        VestingMode::Tranche => {
+            for s in vesting_account.schedules.iter_mut() {
+                if current_timestamp < s.release_time {
+                    revoked_amount += s.amount;
+                    s.amount = 0;
+                }
+            }
+        }
+        VestingMode::Linear => {
+            let vested_amount = calculate_linear_vested(
+                vesting_account.total_amount,
+                vesting_account.start_time,
+                vesting_account.end_time,
+                current_timestamp,
+            )
+            .ok_or(DefiOSError::MathOverflow)?;
+            revoked_amount = vesting_account.total_amount - vested_amount;
+            vesting_account.end_time = current_timestamp.max(vesting_account.start_time);
+        }
+    }
+    vesting_account.total_amount -= revoked_amount;
+
+    require!(revoked_amount > 0, DefiOSError::NoUnvestedTokens);
+    //burnt tokens leave the supply, so they stop being carved out of it as well
+    repository_account.vesting_allocation = repository_account
+        .vesting_allocation
+        .checked_sub(revoked_amount)
+        .ok_or(DefiOSError::MathOverflow)?;
+
+    let repository_account_key = repository_account.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"vesting",
+        repository_account_key.as_ref(),
+        &[vesting_account.bump],
+    ]];
+
+    //burnt rather than parked anywhere the repository creator could withdraw them from
+    burn(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Burn {
+                mint: token_mint.to_account_info(),
+                from: vesting_token_account.to_account_info(),
+                authority: vesting_account.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        revoked_amount,
+    )?;
+
+    emit!(VestingRevoked {
+        repository: repository_account.key(),
+        vesting_account: vesting_account.key(),
+        revoked_amount: revoked_amount
+    });
+
+    Ok(())
+}
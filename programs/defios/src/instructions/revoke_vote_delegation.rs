@@ -0,0 +1,43 @@
+use crate::{
+    error::DefiOSError,
+    event::VoteDelegationRevoked,
+    state::{Issue, IssueStaker},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RevokeVoteDelegation<'info> {
+    pub issue_staker: Signer<'info>,
+    pub issue_account: Account<'info, Issue>,
+    #[account(
+        mut,
+        seeds = [b"issuestaker", issue_account.key().as_ref(), issue_staker.key().as_ref()],
+        bump = issue_staker_account.bump
+    )]
+    pub issue_staker_account: Account<'info, IssueStaker>,
+}
+
+pub fn handler(ctx: Context<RevokeVoteDelegation>) -> Result<()> {
+    let issue_staker = &ctx.accounts.issue_staker;
+    let issue_account = &ctx.accounts.issue_account;
+    let issue_staker_account = &mut ctx.accounts.issue_staker_account;
+
+    let delegate = issue_staker_account
+        .delegate
+        .ok_or(DefiOSError::NotADelegate)?;
+    //once the delegate has voted with this stake the vote stands
+    require!(
+        !issue_staker_account.has_voted,
+        DefiOSError::PullRequestVotingClosedAlready
+    );
+
+    issue_staker_account.delegate = None;
+
+    emit!(VoteDelegationRevoked {
+        issue: issue_account.key(),
+        issue_staker: issue_staker.key(),
+        delegate: delegate
+    });
+
+    Ok(())
+}
@@ -1,17 +1,24 @@
-use crate::constants::{MAX_INT, TOKEN_VEST_AMOUNT, VESTING_NUMBER};
+use crate::constants::MAX_INT;
+use crate::curve::{check_reserve, validate_curve};
 use crate::error::DefiOSError;
-use crate::helper::verify_calc_sell;
-use crate::state::{CommunalAccount, Repository};
+use crate::event::{TokensSold, TradeFeeCollected};
+use crate::helper::{
+    calculate_trade_fee, get_modified_supply, get_modified_tokens, get_transfer_fee,
+    is_whole_token_amount, verify_calc_sell,
+};
+use crate::state::{CommunalAccount, ProgramConfig, Repository, Treasury};
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::{create, get_associated_token_address, AssociatedToken, Create},
-    mint::USDC,
-    token,
-    token::{transfer, Burn, Mint, Token, TokenAccount, Transfer},
+    associated_token::{
+        create, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_interface,
+    token_interface::{
+        transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
 
 #[derive(Accounts)]
-#[instruction(usdc_amount:u64,number_of_tokens:u64)]
 pub struct SellToken<'info> {
     #[account(mut)]
     pub seller: Signer<'info>,
@@ -30,26 +37,25 @@ pub struct SellToken<'info> {
         constraint = communal_token_account.mint==rewards_mint.key(),
         constraint = communal_token_account.owner == communal_deposit.key()
     )]
-    pub communal_token_account: Account<'info, TokenAccount>,
+    pub communal_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         constraint = communal_usdc_account.mint==usdc_mint.key(),
         constraint = communal_usdc_account.owner == communal_deposit.key()
     )]
-    pub communal_usdc_account: Account<'info, TokenAccount>,
+    pub communal_usdc_account: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
-        constraint = seller_token_account.amount >= number_of_tokens@DefiOSError::InsufficientFunds,
         constraint = seller_token_account.owner == seller.key(),
         constraint = seller_token_account.mint == rewards_mint.key()
     )]
-    pub seller_token_account: Account<'info, TokenAccount>,
+    pub seller_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub repository_account: Box<Account<'info, Repository>>,
     ///CHECK: usdc account is setup in function
     #[account(mut)]
     pub seller_usdc_account: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     #[account(
         mut,
         seeds = [
@@ -59,15 +65,91 @@ pub struct SellToken<'info> {
         ],
         bump
     )]
-    pub rewards_mint: Account<'info, Mint>,
-    // #[account(address=USDC)]
-    pub usdc_mint: Account<'info, Mint>,
+    pub rewards_mint: InterfaceAccount<'info, Mint>,
+    #[account(address = program_config.quote_mint @ DefiOSError::InvalidQuoteMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [
+            b"treasury",
+            repository_account.key().as_ref()
+        ],
+        bump = repository_treasury.bump
+    )]
+    pub repository_treasury: Box<Account<'info, Treasury>>,
+    #[account(
+        mut,
+        constraint = repository_treasury_usdc_account.mint == usdc_mint.key(),
+        constraint = repository_treasury_usdc_account.owner == repository_treasury.key()
+    )]
+    pub repository_treasury_usdc_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            b"treasury",
+            b"protocol"
+        ],
+        bump = protocol_treasury.bump
+    )]
+    pub protocol_treasury: Box<Account<'info, Treasury>>,
+    #[account(
+        mut,
+        constraint = protocol_treasury_usdc_account.mint == usdc_mint.key(),
+        constraint = protocol_treasury_usdc_account.owner == protocol_treasury.key()
+    )]
+    pub protocol_treasury_usdc_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub usdc_token_program: Interface<'info, TokenInterface>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<SellToken>, usdc_amount: u64, number_of_tokens: u64) -> Result<()> {
     let rewards_mint = &ctx.accounts.rewards_mint;
+    let repository_account = &ctx.accounts.repository_account;
+
+    require!(
+        validate_curve(&repository_account.curve_config),
+        DefiOSError::RepositoryNotMigrated
+    );
+    let modified_token_supply = get_modified_supply(
+        rewards_mint.supply,
+        repository_account.vesting_allocation,
+        rewards_mint.decimals,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+    let modified_tokens = get_modified_tokens(number_of_tokens, rewards_mint.decimals)
+        .ok_or(DefiOSError::MathOverflow)?;
+
+    require!(
+        (number_of_tokens as u128) < MAX_INT,
+        DefiOSError::MathOverflow
+    );
+    require!(
+        verify_calc_sell(
+            &repository_account.curve_config,
+            modified_token_supply,
+            usdc_amount,
+            modified_tokens
+        ),
+        DefiOSError::IncorrectMaths
+    );
+
+    execute_sell(ctx, usdc_amount, number_of_tokens)?;
+
+    Ok(())
+}
+
+//burns the sold tokens and pays the seller out of the communal usdc account,
+//returns the usdc credited to the seller
+pub fn execute_sell(
+    ctx: Context<SellToken>,
+    usdc_amount: u64,
+    number_of_tokens: u64,
+) -> Result<u64> {
+    let rewards_mint = &mut ctx.accounts.rewards_mint;
     let token_program = &ctx.accounts.token_program;
     let communal_deposit = &mut ctx.accounts.communal_deposit;
     let communal_token_account = &mut ctx.accounts.communal_token_account;
@@ -78,21 +160,44 @@ pub fn handler(ctx: Context<SellToken>, usdc_amount: u64, number_of_tokens: u64)
     let communal_usdc_account = &mut ctx.accounts.communal_usdc_account;
     let system_program = &ctx.accounts.system_program;
     let associated_token_program = &ctx.accounts.associated_token_program;
-
-    let total = VESTING_NUMBER * TOKEN_VEST_AMOUNT * u64::pow(10, rewards_mint.decimals.into());
-    let token_supply = rewards_mint.supply;
-    let modified_token_supply: u64 =
-        (token_supply - total) / (u64::pow(10, rewards_mint.decimals.into()));
-    let modified_tokens: u64 = number_of_tokens / (u64::pow(10, rewards_mint.decimals.into()));
+    let repository_account = &ctx.accounts.repository_account;
+    let repository_treasury_usdc_account = &ctx.accounts.repository_treasury_usdc_account;
+    let protocol_treasury_usdc_account = &ctx.accounts.protocol_treasury_usdc_account;
+    let usdc_token_program = &ctx.accounts.usdc_token_program;
 
     require!(
-        (number_of_tokens as u128) < MAX_INT,
-        DefiOSError::MathOverflow
+        seller_token_account.amount >= number_of_tokens,
+        DefiOSError::InsufficientFunds
     );
+    //the curve prices whole tokens, a fraction of one would be burnt for nothing
     require!(
-        verify_calc_sell(modified_token_supply, usdc_amount, modified_tokens),
-        DefiOSError::IncorrectMaths
+        is_whole_token_amount(number_of_tokens, rewards_mint.decimals)
+            .ok_or(DefiOSError::MathOverflow)?,
+        DefiOSError::FractionalTokenAmount
+    );
+    //the payout and fees leave the reserve, which can never go negative
+    require!(
+        communal_usdc_account.amount >= usdc_amount,
+        DefiOSError::InsufficientReserve
     );
+
+    //trade fee is taken out of the curve payout
+    let (protocol_fee, repository_fee) =
+        calculate_trade_fee(usdc_amount, repository_account.trade_fee_bps)
+            .ok_or(DefiOSError::MathOverflow)?;
+    let seller_payout = usdc_amount
+        .checked_sub(protocol_fee)
+        .and_then(|amount| amount.checked_sub(repository_fee))
+        .ok_or(DefiOSError::MathOverflow)?;
+    //any usdc transfer fee is withheld from what reaches the seller
+    let seller_credit = get_transfer_fee(
+        &usdc_mint.to_account_info(),
+        Clock::get()?.epoch,
+        seller_payout,
+    )
+    .and_then(|transfer_fee| seller_payout.checked_sub(transfer_fee))
+    .ok_or(DefiOSError::MathOverflow)?;
+
     //checks is seller usdc account exists, else creates it
     if seller_usdc_account.data_is_empty() {
         create(CpiContext::new(
@@ -103,28 +208,33 @@ pub fn handler(ctx: Context<SellToken>, usdc_amount: u64, number_of_tokens: u64)
                 authority: seller.to_account_info(),
                 mint: usdc_mint.to_account_info(),
                 system_program: system_program.to_account_info(),
-                token_program: token_program.to_account_info(),
+                token_program: usdc_token_program.to_account_info(),
             },
         ))?;
     }
-    let expected_seller_usdc_account =
-        get_associated_token_address(&seller.key(), &usdc_mint.key());
+    let expected_seller_usdc_account = get_associated_token_address_with_program_id(
+        &seller.key(),
+        &usdc_mint.key(),
+        &usdc_token_program.key(),
+    );
     require!(
         expected_seller_usdc_account.eq(&seller_usdc_account.key()),
         DefiOSError::TokenAccountMismatch
     );
 
     //transfers spl token to communal token account
-    transfer(
+    transfer_checked(
         CpiContext::new(
             token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: seller_token_account.to_account_info(),
+                mint: rewards_mint.to_account_info(),
                 to: communal_token_account.to_account_info(),
                 authority: seller.to_account_info(),
             },
         ),
         number_of_tokens,
+        rewards_mint.decimals,
     )?;
 
     //calculates signer seeds
@@ -149,7 +259,7 @@ pub fn handler(ctx: Context<SellToken>, usdc_amount: u64, number_of_tokens: u64)
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
 
     //Execute anchor's helper function to burn tokens
-    token::burn(cpi_ctx, number_of_tokens)?;
+    token_interface::burn(cpi_ctx, number_of_tokens)?;
 
     //execute function to send usdc to seller
     let rewards_key = rewards_mint.key();
@@ -161,18 +271,87 @@ pub fn handler(ctx: Context<SellToken>, usdc_amount: u64, number_of_tokens: u64)
         &[communal_deposit.bump],
     ]];
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
-            token_program.to_account_info(),
-            Transfer {
+            usdc_token_program.to_account_info(),
+            TransferChecked {
                 from: communal_usdc_account.to_account_info(),
+                mint: usdc_mint.to_account_info(),
                 to: seller_usdc_account.to_account_info(),
                 authority: communal_deposit.to_account_info(),
             },
             communal_signer_seeds,
         ),
-        usdc_amount,
+        seller_payout,
+        usdc_mint.decimals,
     )?;
 
-    Ok(())
+    //execute function to send fees to the treasuries
+    if protocol_fee > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                usdc_token_program.to_account_info(),
+                TransferChecked {
+                    from: communal_usdc_account.to_account_info(),
+                    mint: usdc_mint.to_account_info(),
+                    to: protocol_treasury_usdc_account.to_account_info(),
+                    authority: communal_deposit.to_account_info(),
+                },
+                communal_signer_seeds,
+            ),
+            protocol_fee,
+            usdc_mint.decimals,
+        )?;
+    }
+    if repository_fee > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                usdc_token_program.to_account_info(),
+                TransferChecked {
+                    from: communal_usdc_account.to_account_info(),
+                    mint: usdc_mint.to_account_info(),
+                    to: repository_treasury_usdc_account.to_account_info(),
+                    authority: communal_deposit.to_account_info(),
+                },
+                communal_signer_seeds,
+            ),
+            repository_fee,
+            usdc_mint.decimals,
+        )?;
+    }
+
+    //the reserve left behind has to still cover the curve at the reduced supply
+    rewards_mint.reload()?;
+    communal_usdc_account.reload()?;
+    let supply_after_sale = get_modified_supply(
+        rewards_mint.supply,
+        repository_account.vesting_allocation,
+        rewards_mint.decimals,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+    let (_, deficit) = check_reserve(
+        &repository_account.curve_config,
+        supply_after_sale,
+        communal_usdc_account.amount,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+    require!(deficit == 0, DefiOSError::InsufficientReserve);
+
+    emit!(TradeFeeCollected {
+        repository: repository_account.key(),
+        trader: seller.key(),
+        rewards_mint: rewards_key,
+        protocol_fee: protocol_fee,
+        repository_fee: repository_fee
+    });
+
+    emit!(TokensSold {
+        seller: seller.key(),
+        repository: repository_account.key(),
+        rewards_mint: rewards_key,
+        number_of_tokens: number_of_tokens,
+        usdc_amount: usdc_amount
+    });
+
+    Ok(seller_credit)
 }
@@ -0,0 +1,32 @@
+use crate::curve::validate_curve;
+use crate::error::DefiOSError;
+use crate::helper::get_sell_quote;
+use crate::instructions::sell_tokens::{execute_sell, SellToken};
+use anchor_lang::prelude::*;
+
+pub fn handler(ctx: Context<SellToken>, number_of_tokens: u64, min_usdc_out: u64) -> Result<()> {
+    let rewards_mint = &ctx.accounts.rewards_mint;
+    let repository_account = &ctx.accounts.repository_account;
+
+    require!(
+        validate_curve(&repository_account.curve_config),
+        DefiOSError::RepositoryNotMigrated
+    );
+
+    //prices the trade against the live supply instead of trusting the client
+    let quote = get_sell_quote(
+        repository_account,
+        rewards_mint.supply,
+        rewards_mint.decimals,
+        number_of_tokens,
+        &ctx.accounts.usdc_mint.to_account_info(),
+        Clock::get()?.epoch,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+
+    //bounds what actually reaches the seller, transfer fee withheld
+    let seller_credit = execute_sell(ctx, quote.usdc_amount, number_of_tokens)?;
+    require!(seller_credit >= min_usdc_out, DefiOSError::SlippageExceeded);
+
+    Ok(())
+}
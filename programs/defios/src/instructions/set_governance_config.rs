@@ -0,0 +1,46 @@
+use crate::{
+    error::DefiOSError,
+    event::GovernanceConfigUpdated,
+    helper::validate_governance_config,
+    state::{GovernanceConfig, Repository},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetGovernanceConfig<'info> {
+    #[account(
+        address = repository_account.repository_creator @ DefiOSError::UnauthorizedUser,
+    )]
+    pub repository_creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"repository",
+            repository_account.id.as_bytes(),
+            repository_creator.key().as_ref(),
+        ],
+        bump = repository_account.bump
+    )]
+    pub repository_account: Box<Account<'info, Repository>>,
+}
+
+pub fn handler(
+    ctx: Context<SetGovernanceConfig>,
+    governance_config: GovernanceConfig,
+) -> Result<()> {
+    let repository_account = &mut ctx.accounts.repository_account;
+
+    require!(
+        validate_governance_config(&governance_config),
+        DefiOSError::InvalidGovernanceConfig
+    );
+
+    repository_account.governance_config = governance_config;
+
+    emit!(GovernanceConfigUpdated {
+        repository: repository_account.key(),
+        governance_config: governance_config
+    });
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use crate::{
+    constants::MAX_REWARD_CUTS_BPS, error::DefiOSError, event::RewardCutsUpdated, state::Repository,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetRewardCuts<'info> {
+    #[account(
+        address = repository_account.repository_creator @ DefiOSError::UnauthorizedUser,
+    )]
+    pub repository_creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"repository",
+            repository_account.id.as_bytes(),
+            repository_creator.key().as_ref(),
+        ],
+        bump = repository_account.bump
+    )]
+    pub repository_account: Box<Account<'info, Repository>>,
+}
+
+pub fn handler(
+    ctx: Context<SetRewardCuts>,
+    maintainer_cut_bps: u16,
+    issue_reporter_cut_bps: u16,
+) -> Result<()> {
+    let repository_account = &mut ctx.accounts.repository_account;
+
+    //the contributor always keeps at least the remainder of every reward
+    require!(
+        (maintainer_cut_bps as u32) + (issue_reporter_cut_bps as u32) <= MAX_REWARD_CUTS_BPS as u32,
+        DefiOSError::InvalidRewardCuts
+    );
+
+    repository_account.maintainer_cut_bps = maintainer_cut_bps;
+    repository_account.issue_reporter_cut_bps = issue_reporter_cut_bps;
+
+    emit!(RewardCutsUpdated {
+        repository: repository_account.key(),
+        maintainer_cut_bps: maintainer_cut_bps,
+        issue_reporter_cut_bps: issue_reporter_cut_bps
+    });
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use crate::{
+    error::DefiOSError,
+    event::VotingModeUpdated,
+    state::{Repository, VotingMode},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetVotingMode<'info> {
+    #[account(
+        address = repository_account.repository_creator @ DefiOSError::UnauthorizedUser,
+    )]
+    pub repository_creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"repository",
+            repository_account.id.as_bytes(),
+            repository_creator.key().as_ref(),
+        ],
+        bump = repository_account.bump
+    )]
+    pub repository_account: Box<Account<'info, Repository>>,
+}
+
+pub fn handler(ctx: Context<SetVotingMode>, voting_mode: VotingMode) -> Result<()> {
+    let repository_account = &mut ctx.accounts.repository_account;
+
+    //only issues created from now on pick up the new mode
+    repository_account.voting_mode = voting_mode;
+
+    emit!(VotingModeUpdated {
+        repository: repository_account.key(),
+        voting_mode: voting_mode
+    });
+
+    Ok(())
+}
@@ -1,16 +1,22 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::{
-        create as create_associated_token_account, get_associated_token_address, AssociatedToken,
-        Create,
+        create as create_associated_token_account, get_associated_token_address_with_program_id,
+        AssociatedToken, Create,
     },
-    token::{transfer, Mint, Token, TokenAccount, Transfer},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 use crate::{
+    constants::{BASIS_POINTS, MAX_STAKE_LOCK_DURATION},
     error::DefiOSError,
     event::IssueStaked,
-    state::{Issue, IssueStaker, PullRequest, Repository},
+    helper::{
+        get_amount_after_transfer_fee, get_cast_voting_power, get_conviction_start,
+        get_mode_voting_power, get_stake_voting_power, get_staker_voting_power,
+        normalize_bounty_amount,
+    },
+    state::{BountyStake, Issue, IssueStaker, PullRequest, Repository, VerifiedUser, VotingMode},
 };
 
 #[derive(Accounts)]
@@ -24,7 +30,7 @@ pub struct StakeIssue<'info> {
         constraint = issue_staker_token_account.amount >= transfer_amount @ DefiOSError::InsufficientStakingFunds,
         constraint = issue_staker_token_account.mint == rewards_mint.key()
     )]
-    pub issue_staker_token_account: Account<'info, TokenAccount>,
+    pub issue_staker_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -62,8 +68,12 @@ pub struct StakeIssue<'info> {
     )]
     pub issue_staker_account: Account<'info, IssueStaker>,
 
-    #[account(constraint = rewards_mint.key()==issue_account.issue_token)]
-    pub rewards_mint: Account<'info, Mint>,
+    #[account(
+        constraint = rewards_mint.key() == issue_account.issue_token
+            || issue_account.bounty_pools.iter().any(|pool| pool.mint == rewards_mint.key())
+            @ DefiOSError::MintNotAllowedOnIssue
+    )]
+    pub rewards_mint: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         seeds = [
@@ -74,12 +84,22 @@ pub struct StakeIssue<'info> {
         bump=pull_request_metadata_account.bump
     )]
     pub pull_request_metadata_account: Option<Account<'info, PullRequest>>,
+    #[account(
+        seeds = [
+            issue_staker_verified_user.user_name.as_bytes(),
+            issue_staker_verified_user.user_pubkey.as_ref(),
+            issue_staker_verified_user.name_router.as_ref()
+        ],
+        bump = issue_staker_verified_user.bump,
+        constraint = issue_staker_verified_user.user_pubkey == issue_staker.key() @ DefiOSError::UnauthorizedUser
+    )]
+    pub issue_staker_verified_user: Option<Account<'info, VerifiedUser>>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn handler(ctx: Context<StakeIssue>, transfer_amount: u64) -> Result<()> {
+pub fn handler(ctx: Context<StakeIssue>, transfer_amount: u64, lock_duration: i64) -> Result<()> {
     let issue_staker = &ctx.accounts.issue_staker;
     let issue_account = &mut ctx.accounts.issue_account;
     let issue_staker_account = &mut ctx.accounts.issue_staker_account;
@@ -96,6 +116,17 @@ pub fn handler(ctx: Context<StakeIssue>, transfer_amount: u64) -> Result<()> {
         issue_account.closed_at.is_none(),
         DefiOSError::IssueClosedAlready
     );
+    require!(lock_duration >= 0, DefiOSError::CantEnterTimeBelowZero);
+    require!(
+        lock_duration <= MAX_STAKE_LOCK_DURATION,
+        DefiOSError::InvalidLockDuration
+    );
+    //splitting a stake across wallets beats the square root, so each staker has to be a verified identity
+    require!(
+        issue_account.voting_mode != VotingMode::Quadratic
+            || ctx.accounts.issue_staker_verified_user.is_some(),
+        DefiOSError::VerifiedUserRequired
+    );
 
     //Creating token account if empty
     if issue_token_pool_account.data_is_empty() {
@@ -113,35 +144,120 @@ pub fn handler(ctx: Context<StakeIssue>, transfer_amount: u64) -> Result<()> {
     }
 
     //checks coorect mint accounts sent
-    let expected_issue_token_pool_account =
-        get_associated_token_address(&issue_account.key(), &rewards_mint.key());
+    let expected_issue_token_pool_account = get_associated_token_address_with_program_id(
+        &issue_account.key(),
+        &rewards_mint.key(),
+        &token_program.key(),
+    );
 
-    let expected_issue_staker_token_account =
-        get_associated_token_address(&issue_staker.key(), &rewards_mint.key());
+    let expected_issue_staker_token_account = get_associated_token_address_with_program_id(
+        &issue_staker.key(),
+        &rewards_mint.key(),
+        &token_program.key(),
+    );
     require!(
         expected_issue_token_pool_account.eq(&issue_token_pool_account.key())
             & expected_issue_staker_token_account.eq(&issue_staker_token_account.key()),
         DefiOSError::TokenAccountMismatch
     );
 
-    transfer(
+    transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: issue_staker_token_account.to_account_info(),
+                mint: rewards_mint.to_account_info(),
                 to: issue_token_pool_account.to_account_info(),
                 authority: issue_staker.to_account_info(),
             },
         ),
         transfer_amount,
+        rewards_mint.decimals,
     )?;
 
-    issue_staker_account.staked_amount += transfer_amount;
+    //only what reached the pool after any transfer fee counts as stake
+    let staked_amount = get_amount_after_transfer_fee(
+        &rewards_mint.to_account_info(),
+        Clock::get()?.epoch,
+        transfer_amount,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+
     issue_staker_account.issue_staker_token_account = issue_staker_token_account.key();
     issue_staker_account.bump = ctx.bumps.issue_staker_account;
     issue_staker_account.issue_staker = issue_staker.key();
     issue_staker_account.issue = issue_account.key();
-    let voting_power = transfer_amount;
+
+    //a new lock-up can only extend the one already on the stake
+    issue_staker_account.locked_until = issue_staker_account
+        .locked_until
+        .max(staked_at + lock_duration);
+    let previous_voting_power =
+        get_staker_voting_power(issue_staker_account).ok_or(DefiOSError::MathOverflow)?;
+    //stakes placed once a pull request is up get less say in choosing it
+    let late_stake = issue_account.first_pr_time.is_some();
+
+    //repository token stakes count one to one, bounty mints by the weight set for them
+    let voting_power = if rewards_mint.key() == issue_account.issue_token {
+        let voting_power = get_stake_voting_power(
+            staked_amount,
+            BASIS_POINTS as u16,
+            lock_duration,
+            late_stake,
+        )
+        .ok_or(DefiOSError::MathOverflow)?;
+        issue_staker_account.staked_amount += staked_amount;
+        issue_staker_account.stake_voting_power += voting_power;
+        issue_account.total_stake_amount += staked_amount;
+        voting_power
+    } else {
+        let bounty_pool = issue_account
+            .bounty_pools
+            .iter_mut()
+            .find(|pool| pool.mint == rewards_mint.key())
+            .ok_or(DefiOSError::MintNotAllowedOnIssue)?;
+        //weighs the stake as if it were denominated like the issue token
+        let normalized_amount = normalize_bounty_amount(
+            staked_amount,
+            bounty_pool.mint_decimals,
+            bounty_pool.issue_token_decimals,
+        )
+        .ok_or(DefiOSError::MathOverflow)?;
+        let voting_power = get_stake_voting_power(
+            normalized_amount,
+            bounty_pool.voting_weight_bps,
+            lock_duration,
+            late_stake,
+        )
+        .ok_or(DefiOSError::MathOverflow)?;
+        bounty_pool.total_stake_amount += staked_amount;
+        match issue_staker_account
+            .bounty_stakes
+            .iter_mut()
+            .find(|bounty_stake| bounty_stake.mint == rewards_mint.key())
+        {
+            Some(bounty_stake) => {
+                bounty_stake.staked_amount += staked_amount;
+                bounty_stake.voting_power += voting_power;
+            }
+            None => issue_staker_account.bounty_stakes.push(BountyStake {
+                mint: rewards_mint.key(),
+                staked_amount,
+                voting_power,
+            }),
+        }
+        voting_power
+    };
+
+    issue_staker_account.staked_at = get_conviction_start(
+        issue_staker_account.staked_at,
+        previous_voting_power,
+        voting_power,
+        staked_at,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+    let staker_voting_power = previous_voting_power + voting_power;
+    let voting_mode = issue_account.voting_mode;
 
     if issue_staker_account.has_voted == false {
         issue_staker_account.pr_voting_power += voting_power
@@ -155,22 +271,42 @@ pub fn handler(ctx: Context<StakeIssue>, transfer_amount: u64) -> Result<()> {
                 voted_on == pull_request_metadata_account.key(),
                 DefiOSError::PullRequestAutoUpdate
             );
-            pull_request_metadata_account.total_voted_amount += voting_power;
+            //the vote is recast with the whole stake, which may weigh less under conviction
+            let cast_voting_power = get_cast_voting_power(
+                voting_mode,
+                staker_voting_power,
+                issue_staker_account.staked_at,
+                staked_at,
+            )
+            .ok_or(DefiOSError::MathOverflow)?;
+            pull_request_metadata_account.total_voted_amount = pull_request_metadata_account
+                .total_voted_amount
+                .checked_sub(issue_staker_account.voted_amount)
+                .and_then(|amount| amount.checked_add(cast_voting_power))
+                .ok_or(DefiOSError::MathOverflow)?;
+            issue_account.total_votes_cast = issue_account
+                .total_votes_cast
+                .checked_sub(issue_staker_account.voted_amount)
+                .and_then(|amount| amount.checked_add(cast_voting_power))
+                .ok_or(DefiOSError::MathOverflow)?;
+            issue_staker_account.voted_amount = cast_voting_power;
         } else {
             require!(1 == 0, DefiOSError::PullRequestAutoUpdate);
         }
     };
 
-    issue_account.total_stake_amount += transfer_amount;
+    issue_account.total_voting_power += get_mode_voting_power(voting_mode, staker_voting_power)
+        - get_mode_voting_power(voting_mode, previous_voting_power);
     emit!(IssueStaked {
         issue_staker: issue_staker.key(),
         issue_account: issue_account.key(),
-        staked_amount: transfer_amount,
+        staked_amount: staked_amount,
         rewards_mint: rewards_mint.key(),
         issue_staker_token_account: issue_token_pool_account.key(),
         issue_contribution_link: issue_account.uri.clone(),
         staked_at: staked_at,
-        pr_voting_power: voting_power
+        pr_voting_power: voting_power,
+        locked_until: issue_staker_account.locked_until
     });
 
     Ok(())
@@ -0,0 +1,404 @@
+use crate::curve::{calculate_sell_amount, validate_curve};
+use crate::error::DefiOSError;
+use crate::event::{TokensSwapped, TradeFeeCollected};
+use crate::helper::{
+    calculate_swap_output, calculate_trade_fee, get_inverse_transfer_fee, get_modified_supply,
+    get_modified_tokens, is_whole_token_amount, verify_swap,
+};
+use crate::state::{CommunalAccount, ProgramConfig, Repository, Treasury};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{
+        create, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_interface,
+    token_interface::{
+        transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+#[derive(Accounts)]
+pub struct SwapTokens<'info> {
+    #[account(mut)]
+    pub swapper: Signer<'info>,
+    #[account(
+        mut,
+        constraint = swapper_token_in_account.owner == swapper.key(),
+        constraint = swapper_token_in_account.mint == rewards_mint_in.key()
+    )]
+    pub swapper_token_in_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    ///CHECK: Check for this account done in function call
+    #[account(mut)]
+    pub swapper_token_out_account: UncheckedAccount<'info>,
+    pub repository_in: Box<Account<'info, Repository>>,
+    #[account(constraint = repository_out.key() != repository_in.key())]
+    pub repository_out: Box<Account<'info, Repository>>,
+    #[account(
+        mut,
+        seeds = [
+            b"Miners",
+            b"MinerC",
+            repository_in.key().as_ref()
+        ],
+        bump
+    )]
+    pub rewards_mint_in: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"Miners",
+            b"MinerC",
+            repository_out.key().as_ref()
+        ],
+        bump
+    )]
+    pub rewards_mint_out: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [
+            b"are_we_conscious",
+            b"is love life ?  ",
+            b"arewemadorinlove",
+            rewards_mint_in.key().as_ref()
+        ],
+        bump = communal_deposit_in.bump
+    )]
+    pub communal_deposit_in: Box<Account<'info, CommunalAccount>>,
+    #[account(
+        mut,
+        constraint = communal_token_in_account.mint == rewards_mint_in.key(),
+        constraint = communal_token_in_account.owner == communal_deposit_in.key()
+    )]
+    pub communal_token_in_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = communal_usdc_in_account.mint == usdc_mint.key(),
+        constraint = communal_usdc_in_account.owner == communal_deposit_in.key()
+    )]
+    pub communal_usdc_in_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            b"are_we_conscious",
+            b"is love life ?  ",
+            b"arewemadorinlove",
+            rewards_mint_out.key().as_ref()
+        ],
+        bump = communal_deposit_out.bump
+    )]
+    pub communal_deposit_out: Box<Account<'info, CommunalAccount>>,
+    #[account(
+        mut,
+        constraint = communal_usdc_out_account.mint == usdc_mint.key(),
+        constraint = communal_usdc_out_account.owner == communal_deposit_out.key()
+    )]
+    pub communal_usdc_out_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = program_config.quote_mint @ DefiOSError::InvalidQuoteMint)]
+    pub usdc_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [
+            b"treasury",
+            repository_in.key().as_ref()
+        ],
+        bump = repository_in_treasury.bump
+    )]
+    pub repository_in_treasury: Box<Account<'info, Treasury>>,
+    #[account(
+        mut,
+        constraint = repository_in_treasury_usdc_account.mint == usdc_mint.key(),
+        constraint = repository_in_treasury_usdc_account.owner == repository_in_treasury.key()
+    )]
+    pub repository_in_treasury_usdc_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            b"treasury",
+            repository_out.key().as_ref()
+        ],
+        bump = repository_out_treasury.bump
+    )]
+    pub repository_out_treasury: Box<Account<'info, Treasury>>,
+    #[account(
+        mut,
+        constraint = repository_out_treasury_usdc_account.mint == usdc_mint.key(),
+        constraint = repository_out_treasury_usdc_account.owner == repository_out_treasury.key()
+    )]
+    pub repository_out_treasury_usdc_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            b"treasury",
+            b"protocol"
+        ],
+        bump = protocol_treasury.bump
+    )]
+    pub protocol_treasury: Box<Account<'info, Treasury>>,
+    #[account(
+        mut,
+        constraint = protocol_treasury_usdc_account.mint == usdc_mint.key(),
+        constraint = protocol_treasury_usdc_account.owner == protocol_treasury.key()
+    )]
+    pub protocol_treasury_usdc_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub usdc_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SwapTokens>, number_of_tokens: u64, min_tokens_out: u64) -> Result<()> {
+    let swapper = &ctx.accounts.swapper;
+    let swapper_token_in_account = &ctx.accounts.swapper_token_in_account;
+    let swapper_token_out_account = &ctx.accounts.swapper_token_out_account;
+    let repository_in = &ctx.accounts.repository_in;
+    let repository_out = &ctx.accounts.repository_out;
+    let rewards_mint_in = &ctx.accounts.rewards_mint_in;
+    let rewards_mint_out = &ctx.accounts.rewards_mint_out;
+    let communal_deposit_in = &ctx.accounts.communal_deposit_in;
+    let communal_token_in_account = &ctx.accounts.communal_token_in_account;
+    let communal_usdc_in_account = &ctx.accounts.communal_usdc_in_account;
+    let communal_usdc_out_account = &ctx.accounts.communal_usdc_out_account;
+    let usdc_mint = &ctx.accounts.usdc_mint;
+    let repository_in_treasury_usdc_account = &ctx.accounts.repository_in_treasury_usdc_account;
+    let repository_out_treasury_usdc_account = &ctx.accounts.repository_out_treasury_usdc_account;
+    let protocol_treasury_usdc_account = &ctx.accounts.protocol_treasury_usdc_account;
+    let token_program = &ctx.accounts.token_program;
+    let usdc_token_program = &ctx.accounts.usdc_token_program;
+    let associated_token_program = &ctx.accounts.associated_token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    require!(
+        swapper_token_in_account.amount >= number_of_tokens,
+        DefiOSError::InsufficientFunds
+    );
+    require!(
+        is_whole_token_amount(number_of_tokens, rewards_mint_in.decimals)
+            .ok_or(DefiOSError::MathOverflow)?,
+        DefiOSError::FractionalTokenAmount
+    );
+
+    require!(
+        validate_curve(&repository_in.curve_config) && validate_curve(&repository_out.curve_config),
+        DefiOSError::RepositoryNotMigrated
+    );
+
+    //prices the sell leg on repository in's curve against the live supply
+    let supply_in = get_modified_supply(
+        rewards_mint_in.supply,
+        repository_in.vesting_allocation,
+        rewards_mint_in.decimals,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+    let tokens_in = get_modified_tokens(number_of_tokens, rewards_mint_in.decimals)
+        .ok_or(DefiOSError::MathOverflow)?;
+    let usdc_released = calculate_sell_amount(&repository_in.curve_config, supply_in, tokens_in)
+        .and_then(|amount| u64::try_from(amount).ok())
+        .ok_or(DefiOSError::MathOverflow)?;
+    require!(
+        communal_usdc_in_account.amount >= usdc_released,
+        DefiOSError::InsufficientReserve
+    );
+    let (protocol_fee_in, repository_fee_in) =
+        calculate_trade_fee(usdc_released, repository_in.trade_fee_bps)
+            .ok_or(DefiOSError::MathOverflow)?;
+    let usdc_budget = usdc_released
+        .checked_sub(protocol_fee_in)
+        .and_then(|amount| amount.checked_sub(repository_fee_in))
+        .ok_or(DefiOSError::MathOverflow)?;
+
+    //spends what is left on repository out's curve, dust below one whole token stays in reserve in
+    let supply_out = get_modified_supply(
+        rewards_mint_out.supply,
+        repository_out.vesting_allocation,
+        rewards_mint_out.decimals,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+    let (tokens_out, usdc_amount) = calculate_swap_output(
+        &repository_out.curve_config,
+        supply_out,
+        usdc_budget,
+        repository_out.trade_fee_bps,
+    )
+    .ok_or(DefiOSError::MathOverflow)?;
+    require!(
+        verify_swap(
+            &repository_in.curve_config,
+            &repository_out.curve_config,
+            supply_in,
+            supply_out,
+            tokens_in,
+            tokens_out
+        ),
+        DefiOSError::IncorrectMaths
+    );
+    let (protocol_fee_out, repository_fee_out) =
+        calculate_trade_fee(usdc_amount, repository_out.trade_fee_bps)
+            .ok_or(DefiOSError::MathOverflow)?;
+
+    let number_of_tokens_out = u64::checked_pow(10, rewards_mint_out.decimals.into())
+        .and_then(|unit| tokens_out.checked_mul(unit))
+        .ok_or(DefiOSError::MathOverflow)?;
+    require!(
+        number_of_tokens_out > 0 && number_of_tokens_out >= min_tokens_out,
+        DefiOSError::SlippageExceeded
+    );
+
+    //any usdc transfer fee is covered from the released reserve so repository out is fully backed
+    let reserve_deposit = get_inverse_transfer_fee(
+        &usdc_mint.to_account_info(),
+        Clock::get()?.epoch,
+        usdc_amount,
+    )
+    .and_then(|transfer_fee| usdc_amount.checked_add(transfer_fee))
+    .ok_or(DefiOSError::MathOverflow)?;
+    let usdc_spent = reserve_deposit
+        .checked_add(protocol_fee_in)
+        .and_then(|amount| amount.checked_add(repository_fee_in))
+        .and_then(|amount| amount.checked_add(protocol_fee_out))
+        .and_then(|amount| amount.checked_add(repository_fee_out))
+        .ok_or(DefiOSError::MathOverflow)?;
+    require!(usdc_spent <= usdc_released, DefiOSError::SlippageExceeded);
+
+    //checks if swapper has a token account for repository out else creates it
+    if swapper_token_out_account.data_is_empty() {
+        create(CpiContext::new(
+            associated_token_program.to_account_info(),
+            Create {
+                payer: swapper.to_account_info(),
+                associated_token: swapper_token_out_account.to_account_info(),
+                authority: swapper.to_account_info(),
+                mint: rewards_mint_out.to_account_info(),
+                system_program: system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+    }
+    let expected_swapper_token_out_account = get_associated_token_address_with_program_id(
+        &swapper.key(),
+        &rewards_mint_out.key(),
+        &token_program.key(),
+    );
+    require!(
+        expected_swapper_token_out_account.eq(&swapper_token_out_account.key()),
+        DefiOSError::TokenAccountMismatch
+    );
+
+    //moves tokens of repository in to its communal account and burns them there
+    transfer_checked(
+        CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: swapper_token_in_account.to_account_info(),
+                mint: rewards_mint_in.to_account_info(),
+                to: communal_token_in_account.to_account_info(),
+                authority: swapper.to_account_info(),
+            },
+        ),
+        number_of_tokens,
+        rewards_mint_in.decimals,
+    )?;
+
+    let rewards_in_key = rewards_mint_in.key();
+    let communal_signer_seeds: &[&[&[u8]]] = &[&[
+        b"are_we_conscious",
+        b"is love life ?  ",
+        b"arewemadorinlove",
+        rewards_in_key.as_ref(),
+        &[communal_deposit_in.bump],
+    ]];
+
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Burn {
+                mint: rewards_mint_in.to_account_info(),
+                from: communal_token_in_account.to_account_info(),
+                authority: communal_deposit_in.to_account_info(),
+            },
+            communal_signer_seeds,
+        ),
+        number_of_tokens,
+    )?;
+
+    //moves the reserve backing the bought tokens and the trade fees out of reserve in
+    let usdc_transfers = [
+        (communal_usdc_out_account.to_account_info(), reserve_deposit),
+        (
+            protocol_treasury_usdc_account.to_account_info(),
+            protocol_fee_in + protocol_fee_out,
+        ),
+        (
+            repository_in_treasury_usdc_account.to_account_info(),
+            repository_fee_in,
+        ),
+        (
+            repository_out_treasury_usdc_account.to_account_info(),
+            repository_fee_out,
+        ),
+    ];
+    for (destination, amount) in usdc_transfers {
+        if amount == 0 {
+            continue;
+        }
+        transfer_checked(
+            CpiContext::new_with_signer(
+                usdc_token_program.to_account_info(),
+                TransferChecked {
+                    from: communal_usdc_in_account.to_account_info(),
+                    mint: usdc_mint.to_account_info(),
+                    to: destination,
+                    authority: communal_deposit_in.to_account_info(),
+                },
+                communal_signer_seeds,
+            ),
+            amount,
+            usdc_mint.decimals,
+        )?;
+    }
+
+    //mints tokens of repository out straight to the swapper
+    let bump = ctx.bumps.rewards_mint_out;
+    let repository_out_key = repository_out.key();
+    let mint_signer_seeds: &[&[&[u8]]] =
+        &[&[b"Miners", b"MinerC", repository_out_key.as_ref(), &[bump]]];
+
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token_interface::MintTo {
+                mint: rewards_mint_out.to_account_info(),
+                to: swapper_token_out_account.to_account_info(),
+                authority: rewards_mint_out.to_account_info(),
+            },
+            mint_signer_seeds,
+        ),
+        number_of_tokens_out,
+    )?;
+
+    emit!(TradeFeeCollected {
+        repository: repository_in.key(),
+        trader: swapper.key(),
+        rewards_mint: rewards_in_key,
+        protocol_fee: protocol_fee_in,
+        repository_fee: repository_fee_in
+    });
+
+    emit!(TradeFeeCollected {
+        repository: repository_out.key(),
+        trader: swapper.key(),
+        rewards_mint: rewards_mint_out.key(),
+        protocol_fee: protocol_fee_out,
+        repository_fee: repository_fee_out
+    });
+
+    emit!(TokensSwapped {
+        swapper: swapper.key(),
+        repository_in: repository_in.key(),
+        repository_out: repository_out.key(),
+        number_of_tokens_in: number_of_tokens,
+        number_of_tokens_out: number_of_tokens_out,
+        usdc_amount: usdc_amount
+    });
+
+    Ok(())
+}
@@ -1,39 +1,37 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::get_associated_token_address,
-    token::{transfer, Mint, Token, TokenAccount, Transfer},
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
 };
 
 use crate::{
     error::DefiOSError,
-    state::{Repository, VestingSchedule},
+    helper::calculate_linear_release,
+    state::{Repository, VestingMode, VestingSchedule},
 };
 
 #[derive(Accounts)]
 pub struct UnlockTokens<'info> {
-    #[account(
-        mut,
-        address = repository_account.repository_creator.key() @ DefiOSError::UnauthorizedUser,
-    )]
-    pub repository_creator: Signer<'info>,
+    //whoever holds the vesting destination unlocks, which follows change_vesting_beneficiary
+    pub beneficiary: Signer<'info>,
 
     #[account(
         mut,
-        constraint = repository_creator_token_account.mint.eq(&token_mint.key()),
+        constraint = beneficiary_token_account.mint.eq(&token_mint.key()),
+        constraint = beneficiary_token_account.owner.eq(&beneficiary.key()) @ DefiOSError::UnauthorizedUser,
         address = vesting_account.destination_address,
     )]
-    pub repository_creator_token_account: Account<'info, TokenAccount>,
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         address = vesting_account.mint_address,
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         seeds = [
             b"repository",
             repository_account.id.as_bytes(),
-            repository_creator.key().as_ref(),
+            repository_account.repository_creator.as_ref(),
         ],
         bump=repository_account.bump
     )]
@@ -55,36 +53,43 @@ pub struct UnlockTokens<'info> {
         constraint = vesting_token_account.mint.eq(&token_mint.key()),
         constraint = vesting_token_account.owner.eq(&vesting_account.key())
     )]
-    pub vesting_token_account: Account<'info, TokenAccount>,
+    pub vesting_token_account: InterfaceAccount<'info, TokenAccount>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<UnlockTokens>) -> Result<()> {
     let vesting_account = &mut ctx.accounts.vesting_account;
-    let repository_creator = &mut ctx.accounts.repository_creator;
     let repository_account = &ctx.accounts.repository_account;
     let token_program = &ctx.accounts.token_program;
-    let repository_creator_token_account = &ctx.accounts.repository_creator_token_account;
+    let token_mint = &ctx.accounts.token_mint;
+    let beneficiary_token_account = &ctx.accounts.beneficiary_token_account;
     let vesting_token_account = &ctx.accounts.vesting_token_account;
-    let rewards_mint = &ctx.accounts.token_mint;
     let current_timestamp = Clock::get()?.unix_timestamp;
 
-    let expected_repository_creator_token_account =
-        get_associated_token_address(&repository_creator.key(), &rewards_mint.key());
-
-    require!(
-        expected_repository_creator_token_account.eq(&repository_creator_token_account.key()),
-        DefiOSError::TokenAccountMismatch
-    );
-
     let mut total_transfer_tokens = 0;
-    for s in vesting_account.schedules.iter_mut() {
-        if current_timestamp as u64 >= s.release_time {
-            total_transfer_tokens += s.amount;
-            s.amount = 0;
+    match vesting_account.mode {
+        VestingMode::Tranche => {
+            for s in vesting_account.schedules.iter_mut() {
+                if current_timestamp as u64 >= s.release_time {
+                    total_transfer_tokens += s.amount;
+                    s.amount = 0;
+                }
+            }
+        }
+        VestingMode::Linear => {
+            //streams the pro-rata amount vested since the last unlock
+            total_transfer_tokens = calculate_linear_release(
+                vesting_account.total_amount,
+                vesting_account.released_amount,
+                vesting_account.start_time,
+                vesting_account.end_time,
+                current_timestamp as u64,
+            )
+            .ok_or(DefiOSError::MathOverflow)?;
         }
     }
+    vesting_account.released_amount += total_transfer_tokens;
 
     require!(
         total_transfer_tokens > 0,
@@ -98,17 +103,19 @@ pub fn handler(ctx: Context<UnlockTokens>) -> Result<()> {
         &[vesting_account.bump],
     ]];
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: vesting_token_account.to_account_info(),
-                to: repository_creator_token_account.to_account_info(),
+                mint: token_mint.to_account_info(),
+                to: beneficiary_token_account.to_account_info(),
                 authority: vesting_account.to_account_info(),
             },
             signer_seeds,
         ),
         total_transfer_tokens,
+        token_mint.decimals,
     )?;
 
     Ok(())
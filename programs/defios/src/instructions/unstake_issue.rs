@@ -1,11 +1,16 @@
 use crate::{
     error::DefiOSError,
     event::IssueUnstaked,
+    helper::{
+        get_amount_after_transfer_fee, get_mode_voting_power, get_released_voting_power,
+        get_staker_voting_power,
+    },
     state::{Issue, IssueStaker, Repository},
 };
 use anchor_lang::prelude::*;
-use anchor_spl::token::{
-    close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer,
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
 };
 
 #[derive(Accounts)]
@@ -18,7 +23,7 @@ pub struct UnstakeIssue<'info> {
         constraint = issue_staker_token_account.mint.eq(&issue_token_pool_account.mint),
         constraint = issue_staker_token_account.owner.eq(&issue_staker.key()),
     )]
-    pub issue_staker_token_account: Account<'info, TokenAccount>,
+    pub issue_staker_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -45,11 +50,10 @@ pub struct UnstakeIssue<'info> {
     pub issue_account: Account<'info, Issue>,
 
     #[account(mut)]
-    pub issue_token_pool_account: Account<'info, TokenAccount>,
+    pub issue_token_pool_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        close = issue_staker,
         seeds = [
             b"issuestaker",
             issue_account.key().as_ref(),
@@ -60,25 +64,29 @@ pub struct UnstakeIssue<'info> {
     pub issue_staker_account: Account<'info, IssueStaker>,
 
     #[account(
-        constraint = rewards_mint.key()==issue_account.issue_token,
+        constraint = rewards_mint.key() == issue_account.issue_token
+            || issue_account.bounty_pools.iter().any(|pool| pool.mint == rewards_mint.key())
+            @ DefiOSError::MintNotAllowedOnIssue,
         constraint = rewards_mint.key().eq(&issue_token_pool_account.mint)
     )]
-    pub rewards_mint: Account<'info, Mint>,
+    pub rewards_mint: InterfaceAccount<'info, Mint>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn handler(ctx: Context<UnstakeIssue>) -> Result<()> {
+pub fn handler(ctx: Context<UnstakeIssue>, amount: u64) -> Result<()> {
     let issue_staker = &ctx.accounts.issue_staker;
     let issue_account = &mut ctx.accounts.issue_account;
     let repository_account = &ctx.accounts.repository_account;
     let issue_staker_account = &mut ctx.accounts.issue_staker_account;
     let issue_staker_token_account = &ctx.accounts.issue_staker_token_account;
-    let issue_token_pool_account = &ctx.accounts.issue_token_pool_account;
+    let issue_token_pool_account = &mut ctx.accounts.issue_token_pool_account;
     let rewards_mint = &ctx.accounts.rewards_mint;
 
+    //an expired or cancelled issue only pays stakes back, whatever was locked or voted
+    let refund_only = issue_account.refund_only;
     require!(
-        issue_account.closed_at.is_none(),
+        issue_account.closed_at.is_none() || refund_only,
         DefiOSError::IssueClosedAlready
     );
 
@@ -86,14 +94,33 @@ pub fn handler(ctx: Context<UnstakeIssue>) -> Result<()> {
     let repository_account_key = repository_account.key();
     let issue_creator_key = issue_account.issue_creator.key();
 
+    let is_issue_token = rewards_mint.key() == issue_account.issue_token;
+    let staked_balance = if is_issue_token {
+        issue_staker_account.staked_amount
+    } else {
+        issue_staker_account
+            .bounty_stakes
+            .iter()
+            .find(|bounty_stake| bounty_stake.mint == rewards_mint.key())
+            .map(|bounty_stake| bounty_stake.staked_amount)
+            .unwrap_or(0)
+    };
+
+    require!(staked_balance != 0, DefiOSError::CantUnnstakeBeforeStaking);
+
     require!(
-        issue_staker_account.staked_amount != 0,
-        DefiOSError::CantUnnstakeBeforeStaking
+        issue_staker_account.has_voted == false || refund_only,
+        DefiOSError::CantUnstakeAfterVoting
     );
 
     require!(
-        issue_staker_account.has_voted == false,
-        DefiOSError::CantUnstakeAfterVoting
+        amount > 0 && amount <= staked_balance,
+        DefiOSError::InvalidUnstakeAmount
+    );
+
+    require!(
+        Clock::get()?.unix_timestamp >= issue_staker_account.locked_until || refund_only,
+        DefiOSError::StakeLocked
     );
 
     let signer_seeds: &[&[&[u8]]] = &[&[
@@ -104,40 +131,113 @@ pub fn handler(ctx: Context<UnstakeIssue>) -> Result<()> {
         &[issue_account.bump],
     ]];
 
-    transfer(
+    transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: issue_token_pool_account.to_account_info(),
+                mint: rewards_mint.to_account_info(),
                 to: issue_staker_token_account.to_account_info(),
                 authority: issue_account.to_account_info(),
             },
             signer_seeds,
         ),
-        issue_staker_account.staked_amount,
+        amount,
+        rewards_mint.decimals,
     )?;
-
-    close_account(CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        CloseAccount {
-            account: issue_token_pool_account.to_account_info(),
-            authority: issue_account.to_account_info(),
-            destination: issue_staker.to_account_info(),
-        },
-        signer_seeds,
-    ))?;
+    let unstaked_amount =
+        get_amount_after_transfer_fee(&rewards_mint.to_account_info(), Clock::get()?.epoch, amount)
+            .ok_or(DefiOSError::MathOverflow)?;
+
+    let previous_voting_power =
+        get_staker_voting_power(issue_staker_account).ok_or(DefiOSError::MathOverflow)?;
+    //voting power leaves with the stake in proportion to the part withdrawn
+    let (voting_power, pool_stake_amount) = if is_issue_token {
+        let voting_power = get_released_voting_power(
+            issue_staker_account.stake_voting_power,
+            issue_staker_account.staked_amount,
+            amount,
+        )
+        .ok_or(DefiOSError::MathOverflow)?;
+        issue_staker_account.stake_voting_power -= voting_power;
+        issue_staker_account.staked_amount -= amount;
+        issue_account.total_stake_amount = issue_account
+            .total_stake_amount
+            .checked_sub(amount)
+            .ok_or(DefiOSError::MathOverflow)?;
+        (voting_power, issue_account.total_stake_amount)
+    } else {
+        let bounty_pool = issue_account
+            .bounty_pools
+            .iter_mut()
+            .find(|pool| pool.mint == rewards_mint.key())
+            .ok_or(DefiOSError::MintNotAllowedOnIssue)?;
+        bounty_pool.total_stake_amount = bounty_pool
+            .total_stake_amount
+            .checked_sub(amount)
+            .ok_or(DefiOSError::MathOverflow)?;
+        let mut voting_power = 0;
+        for bounty_stake in issue_staker_account.bounty_stakes.iter_mut() {
+            if bounty_stake.mint == rewards_mint.key() {
+                voting_power = get_released_voting_power(
+                    bounty_stake.voting_power,
+                    bounty_stake.staked_amount,
+                    amount,
+                )
+                .ok_or(DefiOSError::MathOverflow)?;
+                bounty_stake.voting_power -= voting_power;
+                bounty_stake.staked_amount -= amount;
+            }
+        }
+        issue_staker_account
+            .bounty_stakes
+            .retain(|bounty_stake| bounty_stake.staked_amount > 0);
+        (voting_power, bounty_pool.total_stake_amount)
+    };
+
+    //voting power is only held before voting, so it shrinks along with the stake
+    if !issue_staker_account.has_voted {
+        issue_staker_account.pr_voting_power = issue_staker_account
+            .pr_voting_power
+            .checked_sub(voting_power)
+            .ok_or(DefiOSError::MathOverflow)?;
+    }
+    let voting_mode = issue_account.voting_mode;
+    issue_account.total_voting_power = issue_account
+        .total_voting_power
+        .checked_sub(
+            get_mode_voting_power(voting_mode, previous_voting_power)
+                - get_mode_voting_power(voting_mode, previous_voting_power - voting_power),
+        )
+        .ok_or(DefiOSError::MathOverflow)?;
+
+    //pool is shared by every staker on the issue, so it is only closed once it is empty
+    issue_token_pool_account.reload()?;
+    if pool_stake_amount == 0 && issue_token_pool_account.amount == 0 {
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: issue_token_pool_account.to_account_info(),
+                authority: issue_account.to_account_info(),
+                destination: issue_staker.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
 
     emit!(IssueUnstaked {
         issue_account: issue_account.key(),
         issue_staker: issue_staker.key(),
         issue_staker_token_account: issue_staker_token_account.key(),
         rewards_mint: rewards_mint.key(),
-        unstaked_amount: issue_staker_account.staked_amount,
+        unstaked_amount: unstaked_amount,
+        remaining_staked_amount: staked_balance - amount,
         issue_contribution_link: issue_account.uri.clone()
     });
 
-    issue_account.total_stake_amount -= issue_staker_account.staked_amount;
-    issue_staker_account.staked_amount = 0;
+    if issue_staker_account.staked_amount == 0 && issue_staker_account.bounty_stakes.is_empty() {
+        issue_staker_account.close(issue_staker.to_account_info())?;
+    }
 
     Ok(())
 }
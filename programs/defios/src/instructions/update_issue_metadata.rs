@@ -0,0 +1,71 @@
+use crate::{
+    error::DefiOSError,
+    event::IssueUpdated,
+    helper::validate_issue_labels,
+    state::{Issue, IssueDifficulty, IssuePriority, Repository},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateIssueMetadata<'info> {
+    #[account(
+        address = repository_account.repository_creator @ DefiOSError::UnauthorizedUser,
+    )]
+    pub repository_creator: Signer<'info>,
+    #[account(
+        address = issue_account.repository,
+        seeds = [
+            b"repository",
+            repository_account.id.as_bytes(),
+            repository_creator.key().as_ref(),
+        ],
+        bump = repository_account.bump
+    )]
+    pub repository_account: Box<Account<'info, Repository>>,
+    #[account(
+        mut,
+        seeds = [
+            b"issue",
+            issue_account.index.to_string().as_bytes(),
+            repository_account.key().as_ref(),
+            issue_account.issue_creator.key().as_ref(),
+        ],
+        bump = issue_account.bump
+    )]
+    pub issue_account: Account<'info, Issue>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateIssueMetadata>,
+    labels: Option<Vec<String>>,
+    priority: Option<IssuePriority>,
+    difficulty: Option<IssueDifficulty>,
+) -> Result<()> {
+    let repository_account = &ctx.accounts.repository_account;
+    let issue_account = &mut ctx.accounts.issue_account;
+
+    //fields left empty keep their current value
+    if let Some(labels) = labels {
+        require!(
+            validate_issue_labels(&labels),
+            DefiOSError::InvalidIssueLabels
+        );
+        issue_account.labels = labels;
+    }
+    if let Some(priority) = priority {
+        issue_account.priority = priority;
+    }
+    if let Some(difficulty) = difficulty {
+        issue_account.difficulty = difficulty;
+    }
+
+    emit!(IssueUpdated {
+        issue: issue_account.key(),
+        repository: repository_account.key(),
+        labels: issue_account.labels.clone(),
+        priority: issue_account.priority,
+        difficulty: issue_account.difficulty
+    });
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::error::DefiOSError;
+use crate::event::PullRequestUpdated;
+use crate::state::{Issue, PullRequest};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePullRequest<'info> {
+    pub pull_request_addr: Signer<'info>,
+    pub issue: Account<'info, Issue>,
+    #[account(
+        mut,
+        seeds = [
+            b"pullrequestadded",
+            issue.key().as_ref(),
+            pull_request_addr.key().as_ref()
+        ],
+        bump = pull_request_metadata_account.bump
+    )]
+    pub pull_request_metadata_account: Account<'info, PullRequest>,
+}
+
+pub fn handler(ctx: Context<UpdatePullRequest>, metadata_uri: String) -> Result<()> {
+    let pull_request_addr = &ctx.accounts.pull_request_addr;
+    let issue = &ctx.accounts.issue;
+    let pull_request_metadata_account = &mut ctx.accounts.pull_request_metadata_account;
+
+    require!(issue.closed_at.is_none(), DefiOSError::IssueClosedAlready);
+
+    pull_request_metadata_account.metadata_uri = metadata_uri.clone();
+
+    emit!(PullRequestUpdated {
+        sent_by: pull_request_addr.key(),
+        metadata_uri: metadata_uri,
+        issue: issue.key(),
+        pull_request: pull_request_metadata_account.key()
+    });
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::constants::MAX_TRUSTED_NAME_ROUTERS;
+use crate::error::DefiOSError;
+use crate::event::ProgramConfigUpdated;
+use crate::helper::validate_governance_config;
+use crate::state::{GovernanceConfig, ProgramConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateProgramConfig<'info> {
+    #[account(
+        address = program_config.admin @ DefiOSError::UnauthorizedActionAttempted
+    )]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateProgramConfig>,
+    new_admin: Option<Pubkey>,
+    trusted_name_routers: Option<Vec<Pubkey>>,
+    voting_end: Option<i64>,
+    default_governance_config: Option<GovernanceConfig>,
+) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+
+    if let Some(new_admin) = new_admin {
+        program_config.admin = new_admin;
+    }
+    if let Some(trusted_name_routers) = trusted_name_routers {
+        require!(
+            trusted_name_routers.len() <= MAX_TRUSTED_NAME_ROUTERS,
+            DefiOSError::TooManyTrustedNameRouters
+        );
+        program_config.trusted_name_routers = trusted_name_routers;
+    }
+    if let Some(voting_end) = voting_end {
+        require!(voting_end > 0, DefiOSError::InvalidVotingPeriod);
+        program_config.voting_end = voting_end;
+    }
+    //only repositories created afterwards pick up new defaults
+    if let Some(default_governance_config) = default_governance_config {
+        require!(
+            validate_governance_config(&default_governance_config),
+            DefiOSError::InvalidGovernanceConfig
+        );
+        program_config.default_governance_config = default_governance_config;
+    }
+
+    emit!(ProgramConfigUpdated {
+        admin: program_config.admin,
+        quote_mint: program_config.quote_mint,
+        trusted_name_routers: program_config.trusted_name_routers.clone(),
+        voting_end: program_config.voting_end,
+        default_governance_config: program_config.default_governance_config
+    });
+
+    Ok(())
+}
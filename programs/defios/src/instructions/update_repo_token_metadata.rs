@@ -0,0 +1,118 @@
+use crate::error::DefiOSError;
+use crate::event::RepoTokenMetadataUpdated;
+use crate::helper::find_metadata_account;
+use crate::state::Repository;
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::mpl_token_metadata::types::DataV2;
+use anchor_spl::{
+    metadata::{update_metadata_accounts_v2, Metadata, MetadataAccount, UpdateMetadataAccountsV2},
+    token_interface::Mint,
+};
+
+#[derive(Accounts)]
+pub struct UpdateRepoTokenMetadata<'info> {
+    #[account(
+        address = repository_account.repository_creator @ DefiOSError::UnauthorizedUser,
+    )]
+    pub repository_creator: Signer<'info>,
+    #[account(
+        seeds = [
+            b"repository",
+            repository_account.id.as_bytes(),
+            repository_creator.key().as_ref(),
+        ],
+        bump = repository_account.bump
+    )]
+    pub repository_account: Box<Account<'info, Repository>>,
+    #[account(
+        address = repository_account.repo_token,
+        seeds = [
+            b"Miners",
+            b"MinerC",
+            repository_account.key().as_ref()
+        ],
+        bump
+    )]
+    pub rewards_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub metadata: Box<Account<'info, MetadataAccount>>,
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateRepoTokenMetadata>,
+    token_name: Box<Option<String>>,
+    token_symbol: Box<Option<String>>,
+    token_metadata_uri: Box<Option<String>>,
+) -> Result<()> {
+    let repository_account = &ctx.accounts.repository_account;
+    let rewards_mint = &ctx.accounts.rewards_mint;
+    let metadata = &ctx.accounts.metadata;
+    let token_metadata_program = &ctx.accounts.token_metadata_program;
+
+    require!(
+        metadata
+            .key()
+            .eq(&(find_metadata_account(&rewards_mint.key()).0)),
+        DefiOSError::IncorrectMetadataAccount
+    );
+
+    //fields left empty keep their current on-chain value, metaplex pads stored strings with nulls
+    let name = match *token_name {
+        Some(ref token_name) => token_name.clone(),
+        None => metadata.name.trim_end_matches('\0').to_string(),
+    };
+    let symbol = match *token_symbol {
+        Some(ref token_symbol) => token_symbol.clone(),
+        None => metadata.symbol.trim_end_matches('\0').to_string(),
+    };
+    let uri = match *token_metadata_uri {
+        Some(ref token_metadata_uri) => token_metadata_uri.clone(),
+        None => metadata.uri.trim_end_matches('\0').to_string(),
+    };
+
+    let data_v2 = DataV2 {
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+        seller_fee_basis_points: metadata.seller_fee_basis_points,
+        creators: metadata.creators.clone(),
+        collection: metadata.collection.clone(),
+        uses: metadata.uses.clone(),
+    };
+
+    //the rewards mint pda is the update authority set in create_repository
+    let bump = ctx.bumps.rewards_mint;
+    let repository_account_key = repository_account.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"Miners",
+        b"MinerC",
+        repository_account_key.as_ref(),
+        &[bump],
+    ]];
+
+    update_metadata_accounts_v2(
+        CpiContext::new_with_signer(
+            token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: metadata.to_account_info(),
+                update_authority: rewards_mint.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        None,
+        Some(data_v2),
+        None,
+        None,
+    )?;
+
+    emit!(RepoTokenMetadataUpdated {
+        repository: repository_account.key(),
+        rewards_mint: rewards_mint.key(),
+        token_name: name,
+        token_symbol: symbol,
+        token_metadata_uri: uri
+    });
+
+    Ok(())
+}
@@ -1,7 +1,6 @@
-use crate::constants::VOTING_END;
 use crate::error::DefiOSError;
 use crate::event::ObjectiveProposalVoted;
-use crate::state::{Grantee, Objective, ObjectiveProposal, ObjectiveProposalVote};
+use crate::state::{Grantee, Objective, ObjectiveProposal, ObjectiveProposalVote, ProgramConfig};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -44,6 +43,11 @@ pub struct VoteObjective<'info> {
         bump
     )]
     pub objective_proposal_vote: Account<'info, ObjectiveProposalVote>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -56,7 +60,7 @@ pub fn handler(ctx: Context<VoteObjective>, positive: bool) -> Result<()> {
 
     let current_time = Clock::get()?.unix_timestamp;
     require!(
-        current_time - proposal.proposed_at <= VOTING_END,
+        current_time - proposal.proposed_at <= ctx.accounts.program_config.voting_end,
         DefiOSError::VotingPeriodEnded
     );
 
@@ -1,7 +1,7 @@
-use crate::constants::VOTING_END;
 use crate::error::DefiOSError;
 use crate::event::PRVoted;
-use crate::state::{Issue, IssueStaker, PullRequest, Repository};
+use crate::helper::{get_cast_voting_power, get_staker_voting_power};
+use crate::state::{Issue, IssueStaker, ProgramConfig, PullRequest, Repository};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -35,18 +35,24 @@ pub struct VotePRs<'info> {
         seeds = [b"issuestaker", issue_account.key().as_ref(), issue_staker.key().as_ref()],
         bump=issue_staker_account.bump
     )]
-    pub issue_staker_account: Account<'info, IssueStaker>,
+    pub issue_staker_account: Option<Account<'info, IssueStaker>>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<VotePRs>) -> Result<()> {
+//remaining accounts are the stakes delegated to the voter, they are voted together with its own
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, VotePRs<'info>>) -> Result<()> {
     let current_time = Clock::get()?.unix_timestamp;
+    let voter = ctx.accounts.issue_staker.key();
     let issue_account = &mut ctx.accounts.issue_account;
-    let issue_staker_account = &mut ctx.accounts.issue_staker_account;
     let pull_request_metadata_account = &mut ctx.accounts.pull_request_metadata_account;
     let first_pr_time = issue_account.first_pr_time.unwrap();
     require!(
-        current_time - first_pr_time <= VOTING_END.try_into().unwrap(),
+        current_time - first_pr_time <= ctx.accounts.program_config.voting_end,
         DefiOSError::VotingPeriodEnded
     );
 
@@ -54,16 +60,64 @@ pub fn handler(ctx: Context<VotePRs>) -> Result<()> {
         pull_request_metadata_account.accepted == false && issue_account.closed_at.is_none(),
         DefiOSError::PullRequestVotingClosedAlready
     );
-    pull_request_metadata_account.total_voted_amount += issue_staker_account.pr_voting_power;
+
+    let mut vote_amount = 0;
+    let mut voter_count = 0;
+    if let Some(issue_staker_account) = &mut ctx.accounts.issue_staker_account {
+        require!(
+            issue_staker_account.delegate.is_none(),
+            DefiOSError::VoteDelegated
+        );
+        require!(!issue_staker_account.has_voted, DefiOSError::AlreadyVoted);
+        vote_amount = get_cast_voting_power(
+            issue_account.voting_mode,
+            get_staker_voting_power(issue_staker_account).ok_or(DefiOSError::MathOverflow)?,
+            issue_staker_account.staked_at,
+            current_time,
+        )
+        .ok_or(DefiOSError::MathOverflow)?;
+        issue_staker_account.voted_amount = vote_amount;
+        issue_staker_account.pr_voting_power = 0;
+        issue_staker_account.has_voted = true;
+        issue_staker_account.voted_on = Some(pull_request_metadata_account.key());
+        voter_count += 1;
+    }
+
+    let mut delegated_amount = 0;
+    for delegator_info in ctx.remaining_accounts.iter() {
+        let mut delegator = Account::<IssueStaker>::try_from(delegator_info)?;
+        require!(
+            delegator.issue == issue_account.key()
+                && delegator.delegate == Some(voter)
+                && !delegator.has_voted,
+            DefiOSError::NotADelegate
+        );
+        let cast_voting_power = get_cast_voting_power(
+            issue_account.voting_mode,
+            get_staker_voting_power(&delegator).ok_or(DefiOSError::MathOverflow)?,
+            delegator.staked_at,
+            current_time,
+        )
+        .ok_or(DefiOSError::MathOverflow)?;
+        delegated_amount += cast_voting_power;
+        delegator.voted_amount = cast_voting_power;
+        delegator.pr_voting_power = 0;
+        delegator.has_voted = true;
+        delegator.voted_on = Some(pull_request_metadata_account.key());
+        delegator.exit(ctx.program_id)?;
+        voter_count += 1;
+    }
+
+    pull_request_metadata_account.total_voted_amount += vote_amount + delegated_amount;
+    pull_request_metadata_account.voter_count += voter_count;
+    issue_account.total_votes_cast += vote_amount + delegated_amount;
 
     emit!(PRVoted {
         pull_request: pull_request_metadata_account.key(),
-        vote_amount: issue_staker_account.pr_voting_power,
-        voter: ctx.accounts.issue_staker.key()
+        vote_amount: vote_amount + delegated_amount,
+        voter: voter,
+        delegated_amount: delegated_amount
     });
 
-    issue_staker_account.pr_voting_power = 0;
-    issue_staker_account.has_voted = true;
-    issue_staker_account.voted_on = Some(pull_request_metadata_account.key());
     Ok(())
 }
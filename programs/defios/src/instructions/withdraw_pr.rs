@@ -0,0 +1,46 @@
+use crate::error::DefiOSError;
+use crate::event::PullRequestWithdrawn;
+use crate::state::{Issue, PullRequest};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct WithdrawPullRequest<'info> {
+    #[account(mut)]
+    pub pull_request_addr: Signer<'info>,
+    pub issue: Account<'info, Issue>,
+    #[account(
+        mut,
+        close = pull_request_addr,
+        seeds = [
+            b"pullrequestadded",
+            issue.key().as_ref(),
+            pull_request_addr.key().as_ref()
+        ],
+        bump = pull_request_metadata_account.bump
+    )]
+    pub pull_request_metadata_account: Account<'info, PullRequest>,
+}
+
+pub fn handler(ctx: Context<WithdrawPullRequest>) -> Result<()> {
+    let pull_request_addr = &ctx.accounts.pull_request_addr;
+    let issue = &ctx.accounts.issue;
+    let pull_request_metadata_account = &ctx.accounts.pull_request_metadata_account;
+
+    require!(
+        !pull_request_metadata_account.accepted,
+        DefiOSError::PullRequestAlreadyAccepted
+    );
+    //stakers that voted for it would otherwise lose track of their vote, even a zero weight one
+    require!(
+        pull_request_metadata_account.voter_count == 0,
+        DefiOSError::PullRequestHasVotes
+    );
+
+    emit!(PullRequestWithdrawn {
+        sent_by: pull_request_addr.key(),
+        issue: issue.key(),
+        pull_request: pull_request_metadata_account.key()
+    });
+
+    Ok(())
+}
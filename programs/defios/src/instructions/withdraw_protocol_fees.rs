@@ -0,0 +1,79 @@
+use crate::error::DefiOSError;
+use crate::event::TreasuryFeesWithdrawn;
+use crate::state::{ProgramConfig, Treasury};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct WithdrawProtocolFees<'info> {
+    #[account(
+        address = program_config.admin @ DefiOSError::UnauthorizedActionAttempted
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+    #[account(
+        seeds = [
+            b"treasury",
+            b"protocol"
+        ],
+        bump = protocol_treasury.bump
+    )]
+    pub protocol_treasury: Account<'info, Treasury>,
+    #[account(
+        mut,
+        constraint = protocol_treasury_usdc_account.mint == usdc_mint.key(),
+        constraint = protocol_treasury_usdc_account.owner == protocol_treasury.key(),
+        constraint = protocol_treasury_usdc_account.amount >= amount @ DefiOSError::InsufficientFunds
+    )]
+    pub protocol_treasury_usdc_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = destination_usdc_account.mint == usdc_mint.key()
+    )]
+    pub destination_usdc_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = program_config.quote_mint @ DefiOSError::InvalidQuoteMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<WithdrawProtocolFees>, amount: u64) -> Result<()> {
+    let authority = &ctx.accounts.authority;
+    let protocol_treasury = &ctx.accounts.protocol_treasury;
+    let protocol_treasury_usdc_account = &ctx.accounts.protocol_treasury_usdc_account;
+    let destination_usdc_account = &ctx.accounts.destination_usdc_account;
+    let token_program = &ctx.accounts.token_program;
+    let usdc_mint = &ctx.accounts.usdc_mint;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[b"treasury", b"protocol", &[protocol_treasury.bump]]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: protocol_treasury_usdc_account.to_account_info(),
+                mint: usdc_mint.to_account_info(),
+                to: destination_usdc_account.to_account_info(),
+                authority: protocol_treasury.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        usdc_mint.decimals,
+    )?;
+
+    emit!(TreasuryFeesWithdrawn {
+        treasury: protocol_treasury.key(),
+        authority: authority.key(),
+        destination: destination_usdc_account.key(),
+        amount: amount
+    });
+
+    Ok(())
+}
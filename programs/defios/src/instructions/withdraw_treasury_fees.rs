@@ -0,0 +1,125 @@
+use crate::error::DefiOSError;
+use crate::event::TreasuryFeesWithdrawn;
+use crate::state::{ProgramConfig, Repository, Treasury};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{
+        create, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct WithdrawTreasuryFees<'info> {
+    #[account(
+        mut,
+        address = repository_account.repository_creator @ DefiOSError::UnauthorizedUser,
+    )]
+    pub repository_creator: Signer<'info>,
+    #[account(
+        seeds = [
+            b"repository",
+            repository_account.id.as_bytes(),
+            repository_account.repository_creator.as_ref(),
+        ],
+        bump = repository_account.bump
+    )]
+    pub repository_account: Box<Account<'info, Repository>>,
+    #[account(
+        seeds = [
+            b"treasury",
+            repository_account.key().as_ref()
+        ],
+        bump = repository_treasury.bump
+    )]
+    pub repository_treasury: Account<'info, Treasury>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+    #[account(
+        mut,
+        constraint = repository_treasury_usdc_account.mint == usdc_mint.key(),
+        constraint = repository_treasury_usdc_account.owner == repository_treasury.key(),
+        constraint = repository_treasury_usdc_account.amount >= amount @ DefiOSError::InsufficientFunds
+    )]
+    pub repository_treasury_usdc_account: InterfaceAccount<'info, TokenAccount>,
+    ///CHECK: Check for this account done in function call
+    #[account(mut)]
+    pub repository_creator_usdc_account: UncheckedAccount<'info>,
+    //only trade fees in the quote mint are withdrawable, never revoked repository tokens
+    #[account(address = program_config.quote_mint @ DefiOSError::InvalidQuoteMint)]
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<WithdrawTreasuryFees>, amount: u64) -> Result<()> {
+    let repository_creator = &ctx.accounts.repository_creator;
+    let repository_account = &ctx.accounts.repository_account;
+    let repository_treasury = &ctx.accounts.repository_treasury;
+    let repository_treasury_usdc_account = &ctx.accounts.repository_treasury_usdc_account;
+    let repository_creator_usdc_account = &ctx.accounts.repository_creator_usdc_account;
+    let usdc_mint = &ctx.accounts.usdc_mint;
+    let token_program = &ctx.accounts.token_program;
+    let associated_token_program = &ctx.accounts.associated_token_program;
+    let system_program = &ctx.accounts.system_program;
+
+    //checks if repository creator has usdc account else creates it
+    if repository_creator_usdc_account.data_is_empty() {
+        create(CpiContext::new(
+            associated_token_program.to_account_info(),
+            Create {
+                payer: repository_creator.to_account_info(),
+                associated_token: repository_creator_usdc_account.to_account_info(),
+                authority: repository_creator.to_account_info(),
+                mint: usdc_mint.to_account_info(),
+                system_program: system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+    }
+    let expected_repository_creator_usdc_account = get_associated_token_address_with_program_id(
+        &repository_creator.key(),
+        &usdc_mint.key(),
+        &token_program.key(),
+    );
+    require!(
+        expected_repository_creator_usdc_account.eq(&repository_creator_usdc_account.key()),
+        DefiOSError::TokenAccountMismatch
+    );
+
+    let repository_account_key = repository_account.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"treasury",
+        repository_account_key.as_ref(),
+        &[repository_treasury.bump],
+    ]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: repository_treasury_usdc_account.to_account_info(),
+                mint: usdc_mint.to_account_info(),
+                to: repository_creator_usdc_account.to_account_info(),
+                authority: repository_treasury.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        usdc_mint.decimals,
+    )?;
+
+    emit!(TreasuryFeesWithdrawn {
+        treasury: repository_treasury.key(),
+        authority: repository_creator.key(),
+        destination: repository_creator_usdc_account.key(),
+        amount: amount
+    });
+
+    Ok(())
+}
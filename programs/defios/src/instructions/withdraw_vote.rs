@@ -0,0 +1,100 @@
+use crate::{
+    error::DefiOSError,
+    event::VoteWithdrawn,
+    helper::get_staker_voting_power,
+    state::{Issue, IssueStaker, ProgramConfig, PullRequest},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct WithdrawVote<'info> {
+    pub voter: Signer<'info>,
+    #[account(mut)]
+    pub issue_account: Account<'info, Issue>,
+    #[account(
+        mut,
+        seeds = [
+            b"issuestaker",
+            issue_account.key().as_ref(),
+            issue_staker_account.issue_staker.as_ref()
+        ],
+        bump = issue_staker_account.bump
+    )]
+    pub issue_staker_account: Account<'info, IssueStaker>,
+    #[account(
+        mut,
+        seeds = [
+            b"pullrequestadded",
+            issue_account.key().as_ref(),
+            pull_request_metadata_account.sent_by.as_ref()
+        ],
+        bump = pull_request_metadata_account.bump
+    )]
+    pub pull_request_metadata_account: Account<'info, PullRequest>,
+    #[account(
+        seeds = [b"config"],
+        bump = program_config.bump
+    )]
+    pub program_config: Box<Account<'info, ProgramConfig>>,
+}
+
+pub fn handler(ctx: Context<WithdrawVote>) -> Result<()> {
+    let voter = &ctx.accounts.voter;
+    let issue_account = &mut ctx.accounts.issue_account;
+    let issue_staker_account = &mut ctx.accounts.issue_staker_account;
+    let pull_request_metadata_account = &mut ctx.accounts.pull_request_metadata_account;
+
+    //a delegated stake is steered by its delegate, otherwise by the staker itself
+    require!(
+        voter.key()
+            == issue_staker_account
+                .delegate
+                .unwrap_or(issue_staker_account.issue_staker),
+        DefiOSError::UnauthorizedUser
+    );
+    require!(
+        issue_staker_account.has_voted
+            && issue_staker_account.voted_on == Some(pull_request_metadata_account.key()),
+        DefiOSError::NoVoteCast
+    );
+    require!(
+        issue_account.closed_at.is_none(),
+        DefiOSError::PullRequestVotingClosedAlready
+    );
+
+    let first_pr_time = issue_account.first_pr_time.ok_or(DefiOSError::NoPRFound)?;
+    require!(
+        Clock::get()?.unix_timestamp - first_pr_time <= ctx.accounts.program_config.voting_end,
+        DefiOSError::VotingPeriodEnded
+    );
+
+    //the weight goes back to the stake so it can vote again or be unstaked
+    let vote_amount = issue_staker_account.voted_amount;
+    pull_request_metadata_account.total_voted_amount = pull_request_metadata_account
+        .total_voted_amount
+        .checked_sub(vote_amount)
+        .ok_or(DefiOSError::MathOverflow)?;
+    pull_request_metadata_account.voter_count = pull_request_metadata_account
+        .voter_count
+        .checked_sub(1)
+        .ok_or(DefiOSError::MathOverflow)?;
+    issue_account.total_votes_cast = issue_account
+        .total_votes_cast
+        .checked_sub(vote_amount)
+        .ok_or(DefiOSError::MathOverflow)?;
+    issue_staker_account.pr_voting_power =
+        get_staker_voting_power(issue_staker_account).ok_or(DefiOSError::MathOverflow)?;
+    issue_staker_account.voted_amount = 0;
+    issue_staker_account.has_voted = false;
+    issue_staker_account.voted_on = None;
+
+    emit!(VoteWithdrawn {
+        issue: issue_account.key(),
+        issue_staker: issue_staker_account.issue_staker,
+        voter: voter.key(),
+        pull_request: pull_request_metadata_account.key(),
+        vote_amount: vote_amount
+    });
+
+    Ok(())
+}
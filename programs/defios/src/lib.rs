@@ -1,8 +1,12 @@
-use crate::state::{ObjectiveDeliverable, RoadmapOutlook};
+use crate::state::{
+    CurveConfig, GovernanceConfig, IssueDifficulty, IssuePriority, ObjectiveDeliverable,
+    RewardSplit, RoadmapOutlook, TradeQuote, VestingPlan, VotingMode,
+};
 use anchor_lang::prelude::*;
 use instructions::*;
 
 pub mod constants;
+pub mod curve;
 pub mod error;
 pub mod event;
 pub mod helper;
@@ -15,6 +19,36 @@ declare_id!("A4ka2TchTkFPcdtVctj7imhAtKvwapvihcR456aAW663");
 pub mod defios {
     use super::*;
 
+    pub fn init_program_config(
+        ctx: Context<InitProgramConfig>,
+        trusted_name_routers: Vec<Pubkey>,
+        voting_end: Option<i64>,
+        default_governance_config: Option<GovernanceConfig>,
+    ) -> Result<()> {
+        init_program_config::handler(
+            ctx,
+            trusted_name_routers,
+            voting_end,
+            default_governance_config,
+        )
+    }
+
+    pub fn update_program_config(
+        ctx: Context<UpdateProgramConfig>,
+        new_admin: Option<Pubkey>,
+        trusted_name_routers: Option<Vec<Pubkey>>,
+        voting_end: Option<i64>,
+        default_governance_config: Option<GovernanceConfig>,
+    ) -> Result<()> {
+        update_program_config::handler(
+            ctx,
+            new_admin,
+            trusted_name_routers,
+            voting_end,
+            default_governance_config,
+        )
+    }
+
     pub fn create_name_router(
         ctx: Context<CreateNameRouter>,
         signing_domain: String,
@@ -41,6 +75,9 @@ pub mod defios {
         token_name: Box<Option<String>>,
         token_image: Box<Option<String>>,
         token_metadata_uri: Box<Option<String>>,
+        curve_config: Option<CurveConfig>,
+        trade_fee_bps: Option<u16>,
+        vesting_plan: Option<VestingPlan>,
     ) -> Result<()> {
         create_repository::handler(
             ctx,
@@ -50,22 +87,59 @@ pub mod defios {
             token_name,
             token_image,
             token_metadata_uri,
+            curve_config,
+            trade_fee_bps,
+            vesting_plan,
         )
     }
 
-    pub fn add_issue(ctx: Context<AddIssue>, uri: String) -> Result<()> {
-        add_issue::handler(ctx, uri)
+    pub fn add_issue(
+        ctx: Context<AddIssue>,
+        uri: String,
+        deadline: Option<i64>,
+        labels: Vec<String>,
+        priority: Option<IssuePriority>,
+        difficulty: Option<IssueDifficulty>,
+    ) -> Result<()> {
+        add_issue::handler(ctx, uri, deadline, labels, priority, difficulty)
+    }
+
+    pub fn update_issue_metadata(
+        ctx: Context<UpdateIssueMetadata>,
+        labels: Option<Vec<String>>,
+        priority: Option<IssuePriority>,
+        difficulty: Option<IssueDifficulty>,
+    ) -> Result<()> {
+        update_issue_metadata::handler(ctx, labels, priority, difficulty)
+    }
+
+    pub fn expire_issue(ctx: Context<ExpireIssue>) -> Result<()> {
+        expire_issue::handler(ctx)
+    }
+
+    pub fn cancel_issue(ctx: Context<CancelIssue>) -> Result<()> {
+        cancel_issue::handler(ctx)
+    }
+
+    pub fn stake_issue(
+        ctx: Context<StakeIssue>,
+        transfer_amount: u64,
+        lock_duration: i64,
+    ) -> Result<()> {
+        stake_issue::handler(ctx, transfer_amount, lock_duration)
     }
 
-    pub fn stake_issue(ctx: Context<StakeIssue>, transfer_amount: u64) -> Result<()> {
-        stake_issue::handler(ctx, transfer_amount)
+    pub fn add_bounty_mint(ctx: Context<AddBountyMint>, voting_weight_bps: u16) -> Result<()> {
+        add_bounty_mint::handler(ctx, voting_weight_bps)
     }
 
-    pub fn unstake_issue(ctx: Context<UnstakeIssue>) -> Result<()> {
-        unstake_issue::handler(ctx)
+    pub fn unstake_issue(ctx: Context<UnstakeIssue>, amount: u64) -> Result<()> {
+        unstake_issue::handler(ctx, amount)
     }
 
-    pub fn claim_reward(ctx: Context<ClaimReward>) -> Result<()> {
+    pub fn claim_reward<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimReward<'info>>,
+    ) -> Result<()> {
         claim_reward::handler(ctx)
     }
 
@@ -107,14 +181,64 @@ pub mod defios {
         add_pr::handler(ctx, metadata_uri)
     }
 
+    pub fn update_pr(ctx: Context<UpdatePullRequest>, metadata_uri: String) -> Result<()> {
+        update_pr::handler(ctx, metadata_uri)
+    }
+
+    pub fn withdraw_pr(ctx: Context<WithdrawPullRequest>) -> Result<()> {
+        withdraw_pr::handler(ctx)
+    }
+
+    pub fn close_pr(ctx: Context<ClosePullRequest>) -> Result<()> {
+        close_pr::handler(ctx)
+    }
+
     pub fn unlock_tokens(ctx: Context<UnlockTokens>) -> Result<()> {
         unlock_tokens::handler(ctx)
     }
 
+    pub fn change_vesting_beneficiary(ctx: Context<ChangeVestingBeneficiary>) -> Result<()> {
+        change_vesting_beneficiary::handler(ctx)
+    }
+
+    pub fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+        revoke_vesting::handler(ctx)
+    }
+
     pub fn accept_pr(ctx: Context<AcceptPullRequest>, repo_name: String) -> Result<()> {
         accept_pr::handler(ctx, repo_name)
     }
 
+    pub fn set_reward_cuts(
+        ctx: Context<SetRewardCuts>,
+        maintainer_cut_bps: u16,
+        issue_reporter_cut_bps: u16,
+    ) -> Result<()> {
+        set_reward_cuts::handler(ctx, maintainer_cut_bps, issue_reporter_cut_bps)
+    }
+
+    pub fn migrate_repository(ctx: Context<MigrateRepository>) -> Result<()> {
+        migrate_repository::handler(ctx)
+    }
+
+    pub fn set_voting_mode(ctx: Context<SetVotingMode>, voting_mode: VotingMode) -> Result<()> {
+        set_voting_mode::handler(ctx, voting_mode)
+    }
+
+    pub fn set_governance_config(
+        ctx: Context<SetGovernanceConfig>,
+        governance_config: GovernanceConfig,
+    ) -> Result<()> {
+        set_governance_config::handler(ctx, governance_config)
+    }
+
+    pub fn accept_reward_split<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptRewardSplit<'info>>,
+        reward_split: Vec<RewardSplit>,
+    ) -> Result<()> {
+        accept_reward_split::handler(ctx, reward_split)
+    }
+
     pub fn create_communal_account(ctx: Context<RegisterCommunalAccount>) -> Result<()> {
         create_communal_account::handler(ctx)
     }
@@ -135,17 +259,93 @@ pub mod defios {
         sell_tokens::handler(ctx, usdc_amount, number_of_tokens)
     }
 
-    pub fn vote_pr(ctx: Context<VotePRs>) -> Result<()> {
+    pub fn buy_tokens_with_slippage(
+        ctx: Context<BuyToken>,
+        number_of_tokens: u64,
+        max_usdc_in: u64,
+    ) -> Result<()> {
+        buy_tokens_with_slippage::handler(ctx, number_of_tokens, max_usdc_in)
+    }
+
+    pub fn sell_tokens_with_slippage(
+        ctx: Context<SellToken>,
+        number_of_tokens: u64,
+        min_usdc_out: u64,
+    ) -> Result<()> {
+        sell_tokens_with_slippage::handler(ctx, number_of_tokens, min_usdc_out)
+    }
+
+    pub fn quote_buy(ctx: Context<QuoteTrade>, number_of_tokens: u64) -> Result<TradeQuote> {
+        quote_buy::handler(ctx, number_of_tokens)
+    }
+
+    pub fn quote_sell(ctx: Context<QuoteTrade>, number_of_tokens: u64) -> Result<TradeQuote> {
+        quote_sell::handler(ctx, number_of_tokens)
+    }
+
+    pub fn swap_tokens(
+        ctx: Context<SwapTokens>,
+        number_of_tokens: u64,
+        min_tokens_out: u64,
+    ) -> Result<()> {
+        swap_tokens::handler(ctx, number_of_tokens, min_tokens_out)
+    }
+
+    pub fn audit_reserve(ctx: Context<AuditReserve>) -> Result<()> {
+        audit_reserve::handler(ctx)
+    }
+
+    pub fn withdraw_treasury_fees(ctx: Context<WithdrawTreasuryFees>, amount: u64) -> Result<()> {
+        withdraw_treasury_fees::handler(ctx, amount)
+    }
+
+    pub fn withdraw_protocol_fees(ctx: Context<WithdrawProtocolFees>, amount: u64) -> Result<()> {
+        withdraw_protocol_fees::handler(ctx, amount)
+    }
+
+    pub fn vote_pr<'info>(ctx: Context<'_, '_, 'info, 'info, VotePRs<'info>>) -> Result<()> {
         vote_pr::handler(ctx)
     }
 
+    pub fn delegate_vote(ctx: Context<DelegateVote>) -> Result<()> {
+        delegate_vote::handler(ctx)
+    }
+
+    pub fn revoke_vote_delegation(ctx: Context<RevokeVoteDelegation>) -> Result<()> {
+        revoke_vote_delegation::handler(ctx)
+    }
+
+    pub fn change_vote(ctx: Context<ChangeVote>) -> Result<()> {
+        change_vote::handler(ctx)
+    }
+
+    pub fn withdraw_vote(ctx: Context<WithdrawVote>) -> Result<()> {
+        withdraw_vote::handler(ctx)
+    }
+
     pub fn change_repo_token(
         ctx: Context<ChangeRepoToken>,
         token_name: Box<Option<String>>,
         token_symbol: Box<Option<String>>,
         token_metadata_uri: Box<Option<String>>,
+        vesting_plan: Option<VestingPlan>,
+    ) -> Result<()> {
+        change_repo_token::handler(
+            ctx,
+            token_name,
+            token_symbol,
+            token_metadata_uri,
+            vesting_plan,
+        )
+    }
+
+    pub fn update_repo_token_metadata(
+        ctx: Context<UpdateRepoTokenMetadata>,
+        token_name: Box<Option<String>>,
+        token_symbol: Box<Option<String>>,
+        token_metadata_uri: Box<Option<String>>,
     ) -> Result<()> {
-        change_repo_token::handler(ctx, token_name, token_symbol, token_metadata_uri)
+        update_repo_token_metadata::handler(ctx, token_name, token_symbol, token_metadata_uri)
     }
 
     pub fn grant_money(
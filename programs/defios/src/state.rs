@@ -1,3 +1,8 @@
+use crate::constants::{
+    BASIS_POINTS, MAX_BOUNTY_MINTS, MAX_ISSUE_LABELS, MAX_LABEL_LENGTH, MAX_REWARD_SHARES,
+    MAX_TRUSTED_NAME_ROUTERS, MAX_VESTING_SCHEDULES, RELEASE_TIME, TOKEN_VEST_AMOUNT,
+    VESTING_NUMBER,
+};
 use anchor_lang::prelude::*;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
@@ -28,6 +33,119 @@ pub enum RoadmapOutlook {
     LongTerm,
 }
 
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace,
+)]
+#[repr(u8)]
+pub enum IssuePriority {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace,
+)]
+#[repr(u8)]
+pub enum IssueDifficulty {
+    Beginner,
+    #[default]
+    Intermediate,
+    Advanced,
+    Expert,
+}
+
+//how staked voting power turns into votes on pull requests
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default, InitSpace,
+)]
+#[repr(u8)]
+pub enum VotingMode {
+    #[default]
+    Linear,
+    //only verified users can stake, a square root per wallet is no defence against split stakes
+    Quadratic,
+    Conviction,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+#[repr(u8)]
+pub enum CurveType {
+    Linear,
+    Quadratic,
+    Exponential,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct CurveConfig {
+    pub curve_type: CurveType,
+    pub slope: u64,
+    pub base_price: u64,
+}
+
+impl Default for CurveConfig {
+    //reproduces the original n^2 + 2sn pricing used before curves were configurable
+    fn default() -> Self {
+        CurveConfig {
+            curve_type: CurveType::Linear,
+            slope: 2,
+            base_price: 0,
+        }
+    }
+}
+
+//rules accept_issue_vote applies before merging the leading pull request
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct GovernanceConfig {
+    pub quorum_bps: u16,
+    pub approval_threshold_bps: u16,
+    pub allow_early_finalisation: bool,
+}
+
+impl Default for GovernanceConfig {
+    //reproduces the plain majority of all voting power accepted before governance was configurable
+    fn default() -> Self {
+        GovernanceConfig {
+            quorum_bps: 0,
+            approval_threshold_bps: (BASIS_POINTS / 2) as u16,
+            allow_early_finalisation: true,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+#[repr(u8)]
+pub enum VestingMode {
+    Tranche,
+    Linear,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct VestingPlan {
+    pub mode: VestingMode,
+    pub total_allocation: u64,
+    pub cliff: u64,
+    pub number_of_tranches: u8,
+    pub release_interval: u64,
+    pub linear_duration: u64,
+}
+
+impl Default for VestingPlan {
+    //reproduces the fixed schedule used before vesting plans were configurable
+    fn default() -> Self {
+        VestingPlan {
+            mode: VestingMode::Tranche,
+            total_allocation: VESTING_NUMBER * TOKEN_VEST_AMOUNT,
+            cliff: 0,
+            number_of_tranches: VESTING_NUMBER as u8,
+            release_interval: RELEASE_TIME,
+            linear_duration: 0,
+        }
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct NameRouter {
@@ -67,6 +185,13 @@ pub struct Repository {
     pub num_changes: u8,
     pub num_open_issues: u32,
     pub objectives_open: u32,
+    pub curve_config: CurveConfig,
+    pub trade_fee_bps: u16,
+    pub vesting_allocation: u64,
+    pub maintainer_cut_bps: u16,
+    pub issue_reporter_cut_bps: u16,
+    pub voting_mode: VotingMode,
+    pub governance_config: GovernanceConfig,
 }
 
 #[account]
@@ -83,6 +208,53 @@ pub struct Issue {
     pub first_pr_time: Option<i64>,
     pub issue_token: Pubkey,
     pub total_stake_amount: u64,
+    #[max_len(MAX_BOUNTY_MINTS)]
+    pub bounty_pools: Vec<BountyPool>,
+    pub total_voting_power: u64,
+    #[max_len(MAX_REWARD_SHARES)]
+    pub reward_shares: Vec<RewardShare>,
+    pub deadline: Option<i64>,
+    pub refund_only: bool,
+    #[max_len(MAX_ISSUE_LABELS, MAX_LABEL_LENGTH)]
+    pub labels: Vec<String>,
+    pub priority: IssuePriority,
+    pub difficulty: IssueDifficulty,
+    pub voting_mode: VotingMode,
+    pub total_votes_cast: u64,
+    pub maintainer_cut_bps: u16,
+    pub issue_reporter_cut_bps: u16,
+}
+
+//part of an issue's reward a contributor was accepted for
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct RewardShare {
+    pub recipient: Pubkey,
+    pub share_bps: u16,
+    pub claimed: bool,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RewardSplit {
+    pub recipient: Pubkey,
+    pub share_bps: u16,
+}
+
+//extra mint a sponsor can stake on an issue next to the repository token,
+//stakes are brought to the issue token's decimals before the weight applies
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct BountyPool {
+    pub mint: Pubkey,
+    pub voting_weight_bps: u16,
+    pub total_stake_amount: u64,
+    pub mint_decimals: u8,
+    pub issue_token_decimals: u8,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct BountyStake {
+    pub mint: Pubkey,
+    pub staked_amount: u64,
+    pub voting_power: u64,
 }
 
 #[account]
@@ -91,8 +263,13 @@ pub struct VestingSchedule {
     pub bump: u8,
     pub destination_address: Pubkey,
     pub mint_address: Pubkey,
-    #[max_len(10)]
+    #[max_len(MAX_VESTING_SCHEDULES)]
     pub schedules: Vec<Schedule>,
+    pub mode: VestingMode,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub total_amount: u64,
+    pub released_amount: u64,
 }
 
 #[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
@@ -112,6 +289,13 @@ pub struct IssueStaker {
     pub pr_voting_power: u64,
     pub voted_on: Option<Pubkey>,
     pub has_voted: bool,
+    #[max_len(MAX_BOUNTY_MINTS)]
+    pub bounty_stakes: Vec<BountyStake>,
+    pub stake_voting_power: u64,
+    pub locked_until: i64,
+    pub delegate: Option<Pubkey>,
+    pub staked_at: i64,
+    pub voted_amount: u64,
 }
 
 #[account]
@@ -123,6 +307,8 @@ pub struct PullRequest {
     pub metadata_uri: String,
     pub accepted: bool,
     pub total_voted_amount: u64,
+    //stakers whose vote sits on this pull request, zero weight votes included
+    pub voter_count: u64,
 }
 
 #[account]
@@ -172,6 +358,38 @@ pub struct CommunalAccount {
     pub bump: u8,
 }
 
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    pub bump: u8,
+    pub repository: Option<Pubkey>,
+}
+
+//price of a curve trade, returned to clients by quote_buy and quote_sell
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TradeQuote {
+    pub number_of_tokens: u64,
+    pub usdc_amount: u64,
+    pub protocol_fee: u64,
+    pub repository_fee: u64,
+    //quote mint transfer fee, paid on top on a buy and withheld from the payout on a sell
+    pub transfer_fee: u64,
+    //usdc debited from the buyer on a buy, usdc credited to the seller on a sell
+    pub total_usdc: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramConfig {
+    pub bump: u8,
+    pub admin: Pubkey,
+    pub quote_mint: Pubkey,
+    #[max_len(MAX_TRUSTED_NAME_ROUTERS)]
+    pub trusted_name_routers: Vec<Pubkey>,
+    pub voting_end: i64,
+    pub default_governance_config: GovernanceConfig,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Grantee {
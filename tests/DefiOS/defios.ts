@@ -5,11 +5,14 @@ import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   getAssociatedTokenAddress,
   TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
   createMint,
+  mintTo,
   transfer,
   getOrCreateAssociatedTokenAccount,
 } from "@solana/spl-token";
 import { Metaplex } from "@metaplex-foundation/js";
+import { assert } from "chai";
 import { rpcConfig } from "../test_config";
 import * as constant from "../constants";
 import {
@@ -90,7 +93,10 @@ describe("defios", () => {
         constant.repositoryUri,
         constant.tokenName,
         constant.tokenimage,
-        constant.tokenMetadata
+        constant.tokenMetadata,
+        constant.curveConfig,
+        constant.tradeFeeBps,
+        constant.vestingPlan
       )
       .accounts({
         nameRouterAccount,
@@ -106,6 +112,7 @@ describe("defios", () => {
         metadata: metadataAddress,
         tokenMetadataProgram: constant.TOKEN_METADATA_PROGRAM_ID,
         importedMint: null,
+        programConfig: null,
         rent: web3.SYSVAR_RENT_PUBKEY,
       })
       .signers([repositoryCreator])
@@ -117,6 +124,91 @@ describe("defios", () => {
     global.vestingAccount = vestingAccount;
   });
 
+  it("Initializes and updates the program config", async () => {
+    let [nameRouterAccount, repositoryCreator, mintKeypair] = [
+      global.nameRouterAccount,
+      global.repositoryCreator,
+      global.mintKeypair,
+    ];
+    const [programConfig] = await get_pda_from_seeds([Buffer.from("config")]);
+    const [programData] = web3.PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+
+    await program.methods
+      .initProgramConfig([nameRouterAccount], null, null)
+      .accounts({
+        admin: program.provider.publicKey,
+        programConfig,
+        quoteMint: mintKeypair,
+        program: program.programId,
+        programData,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc(rpcConfig);
+
+    //hands admin rights to the repository creator used by later tests
+    await program.methods
+      .updateProgramConfig(repositoryCreator.publicKey, null, null, null)
+      .accounts({
+        admin: program.provider.publicKey,
+        programConfig,
+      })
+      .rpc(rpcConfig);
+
+    //new repositories start from plain majority voting unless the admin changes it
+    const { quoteMint, defaultGovernanceConfig } =
+      await program.account.programConfig.fetch(programConfig);
+    assert.equal(quoteMint.toString(), mintKeypair.toString());
+    assert.deepEqual(defaultGovernanceConfig, {
+      quorumBps: 0,
+      approvalThresholdBps: 5000,
+      allowEarlyFinalisation: true,
+    });
+    global.programConfig = programConfig;
+  });
+
+  it("Refuses to migrate a repository created with the current layout", async () => {
+    try {
+      await program.methods
+        .migrateRepository()
+        .accounts({
+          payer: global.repositoryCreator.publicKey,
+          repositoryAccount: global.repositoryAccount,
+          programConfig: global.programConfig,
+          rewardsMint: global.mintKeypair,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([global.repositoryCreator])
+        .rpc(rpcConfig);
+      assert.fail("migrated a repository twice");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "RepositoryAlreadyMigrated");
+    }
+  });
+
+  it("Updates the repository token metadata", async () => {
+    let [repositoryCreator, repositoryAccount, mintKeypair] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.mintKeypair,
+    ];
+    const metadataAddress = await get_metadata_account(mintKeypair);
+
+    await program.methods
+      .updateRepoTokenMetadata(null, null, constant.tokenMetadata)
+      .accounts({
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryAccount,
+        rewardsMint: mintKeypair,
+        metadata: metadataAddress,
+        tokenMetadataProgram: constant.TOKEN_METADATA_PROGRAM_ID,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+  });
+
   it("Creates a repository with imported spl token", async () => {
     //generates key pairs and airdrops solana to them
     const mintAuthority = await create_keypair();
@@ -155,6 +247,9 @@ describe("defios", () => {
         constant.repositoryUri,
         null,
         null,
+        null,
+        null,
+        null,
         null
       )
       .accounts({
@@ -171,99 +266,127 @@ describe("defios", () => {
         tokenMetadataProgram: constant.TOKEN_METADATA_PROGRAM_ID,
         metadata: null,
         importedMint: mintAddress,
+        programConfig: global.programConfig,
         rent: web3.SYSVAR_RENT_PUBKEY,
       })
       .signers([repositoryCreator])
       .rpc(rpcConfig);
   });
 
-  it("Creates a issue", async () => {
+  it("Creates a repository with an imported token-2022 mint and stakes on it", async () => {
+    const mintAuthority = await create_keypair();
     let [
       nameRouterAccount,
       routerCreatorKeypair,
       repositoryCreator,
-      repositoryAccount,
+      repositoryCreatorVerifiedAccount,
     ] = [
       global.nameRouterAccount,
       global.routerCreatorKeypair,
       global.repositoryCreator,
-      global.repositoryAccount,
+      global.repositoryCreatorVerifiedAccount,
     ];
-
-    const issueCreatorKeypair = await create_keypair();
-
-    const { issueIndex } = await program.account.repository.fetch(
-      repositoryAccount
+    const token2022Id = constant.repositoryId + "5";
+    const [repositoryAccount] = await create_spl_token(
+      repositoryCreator,
+      token2022Id
     );
 
-    // Adding issue creator user
-
-    const [issueVerifiedUser] = await create_verified_user(
-      routerCreatorKeypair,
-      nameRouterAccount,
-      issueCreatorKeypair.publicKey
+    const mintAddress = await createMint(
+      connection,
+      mintAuthority,
+      mintAuthority.publicKey,
+      mintAuthority.publicKey,
+      6,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
     );
-    // Creating issue
-    const issueURI = `https://github.com/${constant.userName}/${constant.repositoryId}/issues/${issueIndex}`;
+
+    await program.methods
+      .createRepository(
+        token2022Id,
+        constant.repositoryTitle,
+        constant.repositoryUri,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        nameRouterAccount,
+        repositoryAccount,
+        repositoryCreatorTokenAccount: null,
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryVerifiedUser: repositoryCreatorVerifiedAccount,
+        rewardsMint: null,
+        routerCreator: routerCreatorKeypair.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+        vestingAccount: null,
+        vestingTokenAccount: null,
+        tokenMetadataProgram: constant.TOKEN_METADATA_PROGRAM_ID,
+        metadata: null,
+        importedMint: mintAddress,
+        programConfig: global.programConfig,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        rent: web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
 
     const [issueAccount] = await get_pda_from_seeds([
       Buffer.from("issue"),
-      Buffer.from(issueIndex.toString()),
+      Buffer.from("0"),
       repositoryAccount.toBuffer(),
-      issueCreatorKeypair.publicKey.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
     ]);
 
     await program.methods
-      .addIssue(issueURI)
+      .addIssue(constant.repositoryUri, null, [], null, null)
       .accounts({
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         issueAccount,
-        issueCreator: issueCreatorKeypair.publicKey,
-        issueVerifiedUser,
+        issueCreator: repositoryCreator.publicKey,
+        issueVerifiedUser: repositoryCreatorVerifiedAccount,
         nameRouterAccount,
         repositoryAccount,
         routerCreator: routerCreatorKeypair.publicKey,
         repositoryCreator: repositoryCreator.publicKey,
         systemProgram: web3.SystemProgram.programId,
       })
-      .signers([issueCreatorKeypair])
+      .signers([repositoryCreator])
       .rpc(rpcConfig);
-    global.issueCreator = issueCreatorKeypair;
-    global.issueVerifiedUser = issueVerifiedUser;
-    global.issueAccount = issueAccount;
-  });
 
-  it("Stakes on a issue", async () => {
-    let [
+    const issueStakerTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
       repositoryCreator,
-      repositoryAccount,
-      mintKeypair,
-      issueAccount,
-      vestingAccount,
-    ] = [
-      global.repositoryCreator,
-      global.repositoryAccount,
-      global.mintKeypair,
-      global.issueAccount,
-      global.vestingAccount,
-    ];
-    const issueTokenPoolAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      issueAccount,
-      true
+      mintAddress,
+      repositoryCreator.publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
     );
-
-    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      repositoryCreator.publicKey
+    await mintTo(
+      connection,
+      mintAuthority,
+      mintAddress,
+      issueStakerTokenAccount.address,
+      mintAuthority,
+      100,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
     );
 
-    const vestingTokenAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      vestingAccount,
-      true
+    const issueTokenPoolAccount = await getAssociatedTokenAddress(
+      mintAddress,
+      issueAccount,
+      true,
+      TOKEN_2022_PROGRAM_ID
     );
-    // Staking tokens on a issue
     const [issueStakerAccount] = await get_pda_from_seeds([
       Buffer.from("issuestaker"),
       issueAccount.toBuffer(),
@@ -271,890 +394,3906 @@ describe("defios", () => {
     ]);
 
     await program.methods
-      .unlockTokens()
-      .accounts({
-        repositoryAccount,
-        repositoryCreatorTokenAccount,
-        repositoryCreator: repositoryCreator.publicKey,
-        systemProgram: web3.SystemProgram.programId,
-        vestingAccount: vestingAccount,
-        tokenMint: mintKeypair,
-        vestingTokenAccount: vestingTokenAccount,
-      })
-      .signers([repositoryCreator])
-      .rpc(rpcConfig);
-
-    await program.methods
-      .stakeIssue(new anchor.BN(10))
+      .stakeIssue(new anchor.BN(10), new anchor.BN(0))
       .accounts({
         issueAccount,
         repositoryAccount,
         issueTokenPoolAccount,
         issueStaker: repositoryCreator.publicKey,
         issueStakerAccount,
-        issueStakerTokenAccount: repositoryCreatorTokenAccount,
-        rewardsMint: mintKeypair,
+        issueStakerTokenAccount: issueStakerTokenAccount.address,
+        rewardsMint: mintAddress,
         systemProgram: web3.SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         pullRequestMetadataAccount: null,
+        issueStakerVerifiedUser: null,
       })
       .signers([repositoryCreator])
       .rpc(rpcConfig);
   });
 
-  it("Unstakes on a issue", async () => {
-    let [repositoryCreator, repositoryAccount, mintKeypair, issueAccount] = [
+  it("Creates a repository with linear vesting and unlocks streamed tokens", async () => {
+    let [
+      nameRouterAccount,
+      routerCreatorKeypair,
+      repositoryCreator,
+      repositoryCreatorVerifiedAccount,
+    ] = [
+      global.nameRouterAccount,
+      global.routerCreatorKeypair,
       global.repositoryCreator,
-      global.repositoryAccount,
-      global.mintKeypair,
-      global.issueAccount,
+      global.repositoryCreatorVerifiedAccount,
     ];
-
-    const issueTokenPoolAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      issueAccount,
-      true
-    );
-    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
+    const linearId = constant.repositoryId + "4";
+    const [
+      repositoryAccount,
+      repositoryCreatorTokenAccount,
+      vestingTokenAccount,
       mintKeypair,
-      repositoryCreator.publicKey
-    );
+      vestingAccount,
+    ] = await create_spl_token(repositoryCreator, linearId);
 
-    const [issueStakerAccount] = await get_pda_from_seeds([
-      Buffer.from("issuestaker"),
-      issueAccount.toBuffer(),
-      repositoryCreator.publicKey.toBuffer(),
-    ]);
+    const metadataAddress = await get_metadata_account(mintKeypair);
 
     await program.methods
-      .unstakeIssue()
+      .createRepository(
+        linearId,
+        constant.repositoryTitle,
+        constant.repositoryUri,
+        constant.tokenName,
+        constant.tokenimage,
+        constant.tokenMetadata,
+        null,
+        null,
+        constant.linearVestingPlan
+      )
       .accounts({
-        issueAccount,
+        nameRouterAccount,
         repositoryAccount,
-        issueTokenPoolAccount,
-        issueStaker: repositoryCreator.publicKey,
-        issueStakerAccount,
-        issueStakerTokenAccount: repositoryCreatorTokenAccount,
+        repositoryCreatorTokenAccount,
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryVerifiedUser: repositoryCreatorVerifiedAccount,
         rewardsMint: mintKeypair,
+        routerCreator: routerCreatorKeypair.publicKey,
         systemProgram: web3.SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        pullRequestMetadataAccount: null,
+        vestingAccount: vestingAccount,
+        vestingTokenAccount: vestingTokenAccount,
+        metadata: metadataAddress,
+        tokenMetadataProgram: constant.TOKEN_METADATA_PROGRAM_ID,
+        importedMint: null,
+        programConfig: global.programConfig,
+        rent: web3.SYSVAR_RENT_PUBKEY,
       })
       .signers([repositoryCreator])
       .rpc(rpcConfig);
 
-    //want to have some stake on issue for future tests
+    await delay(2000);
+
     await program.methods
-      .stakeIssue(new anchor.BN(10))
+      .unlockTokens()
       .accounts({
-        issueAccount,
         repositoryAccount,
-        issueTokenPoolAccount,
-        issueStaker: repositoryCreator.publicKey,
-        issueStakerAccount,
-        issueStakerTokenAccount: repositoryCreatorTokenAccount,
-        rewardsMint: mintKeypair,
+        beneficiaryTokenAccount: repositoryCreatorTokenAccount,
+        beneficiary: repositoryCreator.publicKey,
         systemProgram: web3.SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        pullRequestMetadataAccount: null,
+        vestingAccount: vestingAccount,
+        tokenMint: mintKeypair,
+        vestingTokenAccount: vestingTokenAccount,
       })
       .signers([repositoryCreator])
       .rpc(rpcConfig);
+    global.linearRepositoryAccount = repositoryAccount;
+    global.linearMintKeypair = mintKeypair;
+    global.linearVestingAccount = vestingAccount;
   });
 
-  it("Creates a roadmap!", async () => {
+  it("Holds vested tokens until the cliff and releases them per tranche", async () => {
     let [
-      repositoryCreator,
-      repositoryAccount,
       nameRouterAccount,
-      repositoryCreatorVerifiedAccount,
       routerCreatorKeypair,
+      repositoryCreator,
+      repositoryCreatorVerifiedAccount,
     ] = [
-      global.repositoryCreator,
-      global.repositoryAccount,
       global.nameRouterAccount,
-      global.repositoryCreatorVerifiedAccount,
       global.routerCreatorKeypair,
+      global.repositoryCreator,
+      global.repositoryCreatorVerifiedAccount,
     ];
-
-    const [metadataAccount] = await get_pda_from_seeds([
-      Buffer.from("roadmapmetadataadd"),
-      repositoryAccount.toBuffer(),
-      repositoryCreator.publicKey.toBuffer(),
-    ]);
-
-    await program.methods
-      .addRoadmapData(
-        constant.roadmapTitle,
-        constant.roadmapDescription,
-        constant.roadmapImageUrl,
-        constant.roadmapOutlook
-      )
-      .accounts({
-        nameRouterAccount,
-        metadataAccount,
-        roadmapDataAdder: repositoryCreator.publicKey,
-        roadmapVerifiedUser: repositoryCreatorVerifiedAccount,
-        routerCreator: routerCreatorKeypair.publicKey,
-        repositoryAccount: repositoryAccount,
-        systemProgram: web3.SystemProgram.programId,
-      })
-      .signers([repositoryCreator])
-      .rpc(rpcConfig);
-    global.roadmapMetadataAccount = metadataAccount;
-  });
-  it("Add an objective to a roadmap!", async () => {
-    let [
-      repositoryCreator,
+    const cliffId = constant.repositoryId + "8";
+    const [
       repositoryAccount,
+      repositoryCreatorTokenAccount,
+      vestingTokenAccount,
+      mintKeypair,
+      vestingAccount,
+    ] = await create_spl_token(repositoryCreator, cliffId);
+    const createAccounts = {
       nameRouterAccount,
-      routerCreatorKeypair,
-      repositoryCreatorVerifiedAccount,
-      roadmapMetadataAccount,
-    ] = [
-      global.repositoryCreator,
-      global.repositoryAccount,
-      global.nameRouterAccount,
-      global.routerCreatorKeypair,
-      global.repositoryCreatorVerifiedAccount,
-      global.roadmapMetadataAccount,
+      repositoryAccount,
+      repositoryCreatorTokenAccount,
+      repositoryCreator: repositoryCreator.publicKey,
+      repositoryVerifiedUser: repositoryCreatorVerifiedAccount,
+      rewardsMint: mintKeypair,
+      routerCreator: routerCreatorKeypair.publicKey,
+      systemProgram: web3.SystemProgram.programId,
+      vestingAccount,
+      vestingTokenAccount,
+      metadata: await get_metadata_account(mintKeypair),
+      tokenMetadataProgram: constant.TOKEN_METADATA_PROGRAM_ID,
+      importedMint: null,
+      programConfig: global.programConfig,
+      rent: web3.SYSVAR_RENT_PUBKEY,
+    };
+    const createWithPlan = (vestingPlan) =>
+      program.methods
+        .createRepository(
+          cliffId,
+          constant.repositoryTitle,
+          constant.repositoryUri,
+          constant.tokenName,
+          constant.tokenimage,
+          constant.tokenMetadata,
+          null,
+          null,
+          vestingPlan
+        )
+        .accounts(createAccounts)
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+
+    //plans that would never release anything are refused
+    const invalidPlans = [
+      { ...constant.vestingPlan, totalAllocation: new anchor.BN(0) },
+      { ...constant.vestingPlan, numberOfTranches: 0 },
+      { ...constant.linearVestingPlan, linearDuration: new anchor.BN(0) },
     ];
+    for (const vestingPlan of invalidPlans) {
+      try {
+        await createWithPlan(vestingPlan);
+        assert.fail("created a repository with an invalid vesting plan");
+      } catch (err) {
+        assert.equal(err.error.errorCode.code, "InvalidVestingPlan");
+      }
+    }
 
-    const [objectiveAccount] = await get_pda_from_seeds([
-      Buffer.from("objectivedataadd"),
-      repositoryCreator.publicKey.toBuffer(),
-      Buffer.from(constant.objectiveId),
-    ]);
+    //1200 whole tokens over three tranches a day apart, after a 5 second cliff
+    await createWithPlan({
+      mode: { tranche: {} },
+      totalAllocation: new anchor.BN(1200),
+      cliff: new anchor.BN(5),
+      numberOfTranches: 3,
+      releaseInterval: new anchor.BN(86400),
+      linearDuration: new anchor.BN(0),
+    });
 
-    await program.methods
-      .addObjectiveData(
-        constant.objectiveId,
-        constant.objectiveTitle,
-        constant.objectiveStartUnix,
-        constant.objectiveDescription,
-        constant.objectiveDeliverable
-      )
-      .accounts({
-        nameRouterAccount,
-        metadataAccount: objectiveAccount,
-        roadmapMetadataAccount: roadmapMetadataAccount,
-        parentObjectiveAccount: null,
-        objectiveDataAddr: repositoryCreator.publicKey,
-        objectiveVerifiedUser: repositoryCreatorVerifiedAccount,
-        routerCreator: routerCreatorKeypair.publicKey,
-        repositoryAccount: repositoryAccount,
-        systemProgram: web3.SystemProgram.programId,
-      })
-      .signers([repositoryCreator])
-      .rpc(rpcConfig);
-    global.rootObjectiveAccount = objectiveAccount;
+    const vesting = await program.account.vestingSchedule.fetch(
+      vestingAccount
+    );
+    assert.equal(vesting.schedules.length, 3);
+    assert.equal(
+      vesting.schedules[0].releaseTime.toNumber(),
+      vesting.startTime.toNumber()
+    );
+    for (let i = 0; i < 3; i++) {
+      assert.equal(vesting.schedules[i].amount.toNumber(), 4000);
+      if (i > 0) {
+        assert.equal(
+          vesting.schedules[i].releaseTime.toNumber() -
+            vesting.schedules[i - 1].releaseTime.toNumber(),
+          86400
+        );
+      }
+    }
+
+    const unlock = () =>
+      program.methods
+        .unlockTokens()
+        .accounts({
+          repositoryAccount,
+          beneficiaryTokenAccount: repositoryCreatorTokenAccount,
+          beneficiary: repositoryCreator.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+          vestingAccount,
+          tokenMint: mintKeypair,
+          vestingTokenAccount,
+        })
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+
+    //nothing is released before the cliff
+    try {
+      await unlock();
+      assert.fail("unlocked tokens before the cliff");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "VestingNotReachedRelease");
+    }
+
+    //past the cliff only the first tranche is released
+    await delay(8000);
+    await unlock();
+    const released = await connection.getTokenAccountBalance(
+      repositoryCreatorTokenAccount
+    );
+    assert.equal(released.value.amount, "4000");
+    const remaining = await connection.getTokenAccountBalance(
+      vestingTokenAccount
+    );
+    assert.equal(remaining.value.amount, "8000");
   });
-  it("Add a child objective to an objective", async () => {
+
+  it("Creates a issue", async () => {
     let [
-      repositoryCreator,
-      repositoryAccount,
       nameRouterAccount,
       routerCreatorKeypair,
-      repositoryCreatorVerifiedAccount,
-      objectiveAccount,
+      repositoryCreator,
+      repositoryAccount,
     ] = [
-      global.repositoryCreator,
-      global.repositoryAccount,
       global.nameRouterAccount,
       global.routerCreatorKeypair,
-      global.repositoryCreatorVerifiedAccount,
-      global.rootObjectiveAccount,
-    ];
-
-    const [objectiveAccount2] = await get_pda_from_seeds([
-      Buffer.from("objectivedataadd"),
-      repositoryCreator.publicKey.toBuffer(),
-      Buffer.from("2"),
-    ]);
-    await program.methods
-      .addObjectiveData(
-        "2",
-        constant.objectiveTitle,
-        constant.objectiveStartUnix,
-        constant.objectiveDescription,
-        constant.objectiveDeliverable
-      )
-      .accounts({
-        nameRouterAccount,
-        metadataAccount: objectiveAccount2,
-        objectiveDataAddr: repositoryCreator.publicKey,
-        roadmapMetadataAccount: null,
-        parentObjectiveAccount: objectiveAccount,
-        objectiveVerifiedUser: repositoryCreatorVerifiedAccount,
-        repositoryAccount: repositoryAccount,
-        routerCreator: routerCreatorKeypair.publicKey,
-        systemProgram: web3.SystemProgram.programId,
-      })
-      .signers([repositoryCreator])
-      .rpc(rpcConfig);
-  });
-  it("Adds a PR to an issue", async () => {
-    let [
-      repositoryAccount,
-      mintKeypair,
-      issueAccount,
-      routerCreatorKeypair,
-      nameRouterAccount,
-    ] = [
+      global.repositoryCreator,
       global.repositoryAccount,
-      global.mintKeypair,
-      global.issueAccount,
-      global.routerCreatorKeypair,
-      global.nameRouterAccount,
     ];
 
-    const pullRequestCreator = await create_keypair();
-    const [pullRequestCreatorVerifiedAccount] = await create_verified_user(
+    const issueCreatorKeypair = await create_keypair();
+
+    const { issueIndex } = await program.account.repository.fetch(
+      repositoryAccount
+    );
+
+    // Adding issue creator user
+
+    const [issueVerifiedUser] = await create_verified_user(
       routerCreatorKeypair,
       nameRouterAccount,
-      pullRequestCreator.publicKey
+      issueCreatorKeypair.publicKey
     );
+    // Creating issue
+    const issueURI = `https://github.com/${constant.userName}/${constant.repositoryId}/issues/${issueIndex}`;
 
-    const [pullRequestMetadataAccount] = await get_pda_from_seeds([
-      Buffer.from("pullrequestadded"),
-      issueAccount.toBuffer(),
-      pullRequestCreator.publicKey.toBuffer(),
+    const [issueAccount] = await get_pda_from_seeds([
+      Buffer.from("issue"),
+      Buffer.from(issueIndex.toString()),
+      repositoryAccount.toBuffer(),
+      issueCreatorKeypair.publicKey.toBuffer(),
     ]);
 
-    const pullRequestTokenAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      pullRequestMetadataAccount,
-      true
-    );
+    //the maintainer and the issue reporter take a cut of every reward posted from now on
+    await program.methods
+      .setRewardCuts(1000, 500)
+      .accounts({
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryAccount,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
 
     await program.methods
-      .addPr(constant.pullRequestMetadataUri)
+      .addIssue(issueURI, null, [], null, null)
       .accounts({
-        pullRequestVerifiedUser: pullRequestCreatorVerifiedAccount,
-        issue: issueAccount,
-        pullRequestMetadataAccount: pullRequestMetadataAccount,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        issueAccount,
+        issueCreator: issueCreatorKeypair.publicKey,
+        issueVerifiedUser,
         nameRouterAccount,
-        pullRequestTokenAccount,
-        pullRequestAddr: pullRequestCreator.publicKey,
         repositoryAccount,
         routerCreator: routerCreatorKeypair.publicKey,
+        repositoryCreator: repositoryCreator.publicKey,
         systemProgram: web3.SystemProgram.programId,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        rewardsMint: mintKeypair,
-        tokenProgram: TOKEN_PROGRAM_ID,
       })
-      .signers([pullRequestCreator])
+      .signers([issueCreatorKeypair])
       .rpc(rpcConfig);
-    global.pullRequestCreator = pullRequestCreator;
-    global.pullRequestCreatorVerifiedAccount =
-      pullRequestCreatorVerifiedAccount;
-    global.pullRequestMetadataAccount = pullRequestMetadataAccount;
+    global.issueCreator = issueCreatorKeypair;
+    global.issueVerifiedUser = issueVerifiedUser;
+    global.issueAccount = issueAccount;
   });
-  it("Accepts a PR", async () => {
-    let [
-      repositoryCreator,
-      repositoryAccount,
-      pullRequestMetadataAccount,
-      pullRequestCreator,
-      issueAccount,
-    ] = [
+
+  it("Updates the labels, priority and difficulty of a issue", async () => {
+    let [repositoryCreator, repositoryAccount, issueAccount] = [
       global.repositoryCreator,
       global.repositoryAccount,
-      global.pullRequestMetadataAccount,
-      global.pullRequestCreator,
       global.issueAccount,
     ];
 
     await program.methods
-      .acceptPr(constant.repositoryId)
+      .updateIssueMetadata(["bug", "good first issue"], { high: {} }, null)
       .accounts({
-        pullRequestAddr: pullRequestCreator.publicKey,
-        pullRequestMetadataAccount,
         repositoryCreator: repositoryCreator.publicKey,
         repositoryAccount,
-        issue: issueAccount,
-        systemProgram: web3.SystemProgram.programId,
+        issueAccount,
       })
       .signers([repositoryCreator])
       .rpc(rpcConfig);
+
+    const issue = await program.account.issue.fetch(issueAccount);
+    assert.deepEqual(issue.labels, ["bug", "good first issue"]);
+    assert.deepEqual(issue.priority, { high: {} });
+    assert.deepEqual(issue.difficulty, { intermediate: {} });
   });
 
-  it("Getting a PR accepted and getting rewarded for it", async () => {
+  it("Stakes on a issue", async () => {
     let [
       repositoryCreator,
       repositoryAccount,
-      pullRequestMetadataAccount,
-      pullRequestCreator,
-      issueAccount,
       mintKeypair,
-      issueCreator,
+      issueAccount,
+      vestingAccount,
     ] = [
       global.repositoryCreator,
       global.repositoryAccount,
-      global.pullRequestMetadataAccount,
-      global.pullRequestCreator,
-      global.issueAccount,
       global.mintKeypair,
-      global.issueCreator,
+      global.issueAccount,
+      global.vestingAccount,
     ];
+    const issueTokenPoolAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      issueAccount,
+      true
+    );
 
-    const pullRequestCreatorRewardAccount = await getAssociatedTokenAddress(
+    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
       mintKeypair,
-      pullRequestCreator.publicKey
+      repositoryCreator.publicKey
     );
 
-    const issueTokenPoolAccount = await getAssociatedTokenAddress(
+    const vestingTokenAccount = await getAssociatedTokenAddress(
       mintKeypair,
-      issueAccount,
+      vestingAccount,
       true
     );
+    // Staking tokens on a issue
+    const [issueStakerAccount] = await get_pda_from_seeds([
+      Buffer.from("issuestaker"),
+      issueAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
 
     await program.methods
-      .claimReward()
+      .unlockTokens()
       .accounts({
-        pullRequestCreator: pullRequestCreator.publicKey,
-        pullRequest: pullRequestMetadataAccount,
-        pullRequestCreatorRewardAccount,
-        repositoryCreator: repositoryCreator.publicKey,
-        rewardsMint: mintKeypair,
         repositoryAccount,
-        issueAccount: issueAccount,
-        issueTokenPoolAccount,
-        issueCreator: issueCreator.publicKey,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        beneficiaryTokenAccount: repositoryCreatorTokenAccount,
+        beneficiary: repositoryCreator.publicKey,
         systemProgram: web3.SystemProgram.programId,
+        vestingAccount: vestingAccount,
+        tokenMint: mintKeypair,
+        vestingTokenAccount: vestingTokenAccount,
       })
-      .signers([pullRequestCreator])
+      .signers([repositoryCreator])
       .rpc(rpcConfig);
-  });
-
-  it("Create communal account to store tokens", async () => {
-    let [repositoryCreator, mintKeypair] = [
-      global.repositoryCreator,
-      global.mintKeypair,
-    ];
-
-    const [communal_account] = await get_pda_from_seeds([
-      Buffer.from("are_we_conscious"),
-      Buffer.from("is love life ?  "),
-      Buffer.from("arewemadorinlove"),
-      mintKeypair.toBuffer(),
-    ]);
-
-    const communalTokenAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      communal_account,
-      true
-    );
 
     await program.methods
-      .createCommunalAccount()
+      .stakeIssue(new anchor.BN(10), new anchor.BN(0))
       .accounts({
-        authority: repositoryCreator.publicKey,
-        communalDeposit: communal_account,
-        communalTokenAccount: communalTokenAccount,
-        systemProgram: web3.SystemProgram.programId,
+        issueAccount,
+        repositoryAccount,
+        issueTokenPoolAccount,
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount,
+        issueStakerTokenAccount: repositoryCreatorTokenAccount,
         rewardsMint: mintKeypair,
+        systemProgram: web3.SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        usdcMint: mintKeypair,
-        communalUsdcAccount: communalTokenAccount,
+        pullRequestMetadataAccount: null,
+        issueStakerVerifiedUser: null,
       })
       .signers([repositoryCreator])
       .rpc(rpcConfig);
-    global.communalAccount = communal_account;
   });
 
-  it("Sends a buy transaction", async () => {
-    let [repositoryCreator, mintKeypair, communalAccount, repositoryAccount] = [
+  it("Unstakes on a issue", async () => {
+    let [repositoryCreator, repositoryAccount, mintKeypair, issueAccount] = [
       global.repositoryCreator,
-      global.mintKeypair,
-      global.communalAccount,
       global.repositoryAccount,
+      global.mintKeypair,
+      global.issueAccount,
     ];
 
-    const communalTokenAccount = await getAssociatedTokenAddress(
+    const issueTokenPoolAccount = await getAssociatedTokenAddress(
       mintKeypair,
-      communalAccount,
+      issueAccount,
       true
     );
-
     const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
       mintKeypair,
       repositoryCreator.publicKey
     );
 
+    const [issueStakerAccount] = await get_pda_from_seeds([
+      Buffer.from("issuestaker"),
+      issueAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+
+    const unstakeAccounts = {
+      issueAccount,
+      repositoryAccount,
+      issueTokenPoolAccount,
+      issueStaker: repositoryCreator.publicKey,
+      issueStakerAccount,
+      issueStakerTokenAccount: repositoryCreatorTokenAccount,
+      rewardsMint: mintKeypair,
+      systemProgram: web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    //partial unstake keeps the staker account and the shared pool alive
     await program.methods
-      .buyTokens(new anchor.BN(1), new anchor.BN(1))
-      .accounts({
-        buyer: repositoryCreator.publicKey,
-        communalDeposit: communalAccount,
-        communalTokenAccount: communalTokenAccount,
-        rewardsMint: mintKeypair,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        repositoryAccount: repositoryAccount,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        systemProgram: web3.SystemProgram.programId,
-        buyerTokenAccount: repositoryCreatorTokenAccount,
-        communalUsdcAccount: communalTokenAccount,
-        buyerUsdcAccount: repositoryCreatorTokenAccount,
-        usdcMint: mintKeypair,
-      })
+      .unstakeIssue(new anchor.BN(4))
+      .accounts(unstakeAccounts)
       .signers([repositoryCreator])
       .rpc(rpcConfig);
-  });
-
-  it("Sends a sell transaction", async () => {
-    let [repositoryCreator, mintKeypair, communalAccount, repositoryAccount] = [
-      global.repositoryCreator,
-      global.mintKeypair,
-      global.communalAccount,
-      global.repositoryAccount,
-    ];
 
-    const communalTokenAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      communalAccount,
-      true
+    const issueStaker = await program.account.issueStaker.fetch(
+      issueStakerAccount
     );
+    assert.equal(issueStaker.stakedAmount.toNumber(), 6);
+    assert.equal(issueStaker.prVotingPower.toNumber(), 6);
 
-    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      repositoryCreator.publicKey
-    );
+    //unstaking the rest closes the staker account and the now empty pool
+    await program.methods
+      .unstakeIssue(new anchor.BN(6))
+      .accounts(unstakeAccounts)
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
 
+    assert.isNull(await connection.getAccountInfo(issueStakerAccount));
+
+    //want to have some stake on issue for future tests
     await program.methods
-      .sellTokens(new anchor.BN(0), new anchor.BN(1))
+      .stakeIssue(new anchor.BN(10), new anchor.BN(0))
       .accounts({
-        seller: repositoryCreator.publicKey,
-        communalDeposit: communalAccount,
-        communalTokenAccount: communalTokenAccount,
+        issueAccount,
+        repositoryAccount,
+        issueTokenPoolAccount,
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount,
+        issueStakerTokenAccount: repositoryCreatorTokenAccount,
         rewardsMint: mintKeypair,
-        repositoryAccount: repositoryAccount,
+        systemProgram: web3.SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        systemProgram: web3.SystemProgram.programId,
-        sellerTokenAccount: repositoryCreatorTokenAccount,
-        communalUsdcAccount: communalTokenAccount,
-        sellerUsdcAccount: repositoryCreatorTokenAccount,
-        usdcMint: mintKeypair,
+        pullRequestMetadataAccount: null,
+        issueStakerVerifiedUser: null,
       })
       .signers([repositoryCreator])
       .rpc(rpcConfig);
   });
 
-  it("Grant money to objective", async () => {
-    let [mintKeypair, repositoryAccount, objectiveAccount, repositoryCreator] =
-      [
-        global.mintKeypair,
-        global.repositoryAccount,
-        global.rootObjectiveAccount,
-        global.repositoryCreator,
-      ];
-
-    const grantee = await create_keypair();
-    const [verifiedUserAccount] = await create_verified_user(
-      global.routerCreatorKeypair,
-      global.nameRouterAccount,
-      grantee.publicKey
-    );
-
-    const [granteeAccount] = await get_pda_from_seeds([
-      grantee.publicKey.toBuffer(),
-      repositoryAccount.toBuffer(),
-      objectiveAccount.toBuffer(),
-    ]);
-
-    const objectiveStakeAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      objectiveAccount,
-      true
-    );
+  it("Stakes a second mint as a bounty on a issue", async () => {
+    let [repositoryCreator, repositoryAccount, issueAccount] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.issueAccount,
+    ];
 
-    const granteeStakeAccount = await getOrCreateAssociatedTokenAccount(
+    const bountyMint = await createMint(
       connection,
-      grantee,
-      mintKeypair,
-      grantee.publicKey,
-      false
+      repositoryCreator,
+      repositoryCreator.publicKey,
+      repositoryCreator.publicKey,
+      2
     );
-
-    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
-      mintKeypair,
+    const bountyStakerTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      repositoryCreator,
+      bountyMint,
       repositoryCreator.publicKey
     );
-
-    await transfer(
+    await mintTo(
       connection,
       repositoryCreator,
-      repositoryCreatorTokenAccount,
-      granteeStakeAccount.address,
+      bountyMint,
+      bountyStakerTokenAccount.address,
       repositoryCreator,
-      10
+      1000
     );
 
+    //bounty stakes count for half their amount when voting on PRs
     await program.methods
-      .grantMoney(new anchor.BN(10), constant.roadmapImageUrl)
+      .addBountyMint(5000)
       .accounts({
-        grantee: grantee.publicKey,
-        granteeVerifiedUser: verifiedUserAccount,
-        objective: objectiveAccount,
-        repository: repositoryAccount,
-        tokenMint: mintKeypair,
-        granteeAccount: granteeAccount,
-        objectiveStakeAccount: objectiveStakeAccount,
-        granteeStakeAccount: granteeStakeAccount.address,
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryAccount,
+        issueAccount,
+        bountyMint,
+        issueToken: global.mintKeypair,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const bountyPoolAccount = await getAssociatedTokenAddress(
+      bountyMint,
+      issueAccount,
+      true
+    );
+    const [issueStakerAccount] = await get_pda_from_seeds([
+      Buffer.from("issuestaker"),
+      issueAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+
+    await program.methods
+      .stakeIssue(new anchor.BN(500), new anchor.BN(0))
+      .accounts({
+        issueAccount,
+        repositoryAccount,
+        issueTokenPoolAccount: bountyPoolAccount,
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount,
+        issueStakerTokenAccount: bountyStakerTokenAccount.address,
+        rewardsMint: bountyMint,
         systemProgram: web3.SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        pullRequestMetadataAccount: null,
+        issueStakerVerifiedUser: null,
       })
-      .signers([grantee])
+      .signers([repositoryCreator])
       .rpc(rpcConfig);
-    global.grantee = grantee;
-    global.granteeAccount = granteeAccount;
+
+    const issueStaker = await program.account.issueStaker.fetch(
+      issueStakerAccount
+    );
+    //500 raw at 2 decimals is 50 raw at the repository token's 1 decimal, at half weight
+    assert.equal(issueStaker.bountyStakes[0].stakedAmount.toNumber(), 500);
+    assert.equal(issueStaker.prVotingPower.toNumber(), 10 + 25);
+    global.bountyMint = bountyMint;
   });
-  it("Disperse grant money", async () => {
-    let [
-      repositoryAccount,
-      objectiveAccount,
-      repositoryCreator,
-      issueAccount,
-      mintKeypair,
-    ] = [
-      global.repositoryAccount,
-      global.rootObjectiveAccount,
+
+  it("Locks a stake on a issue for extra voting power", async () => {
+    let [repositoryCreator, repositoryAccount, mintKeypair, issueAccount] = [
       global.repositoryCreator,
-      global.issueAccount,
+      global.repositoryAccount,
       global.mintKeypair,
+      global.issueAccount,
     ];
 
-    const objectiveStakeAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      objectiveAccount,
-      true
-    );
     const issueTokenPoolAccount = await getAssociatedTokenAddress(
       mintKeypair,
       issueAccount,
       true
     );
+    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryCreator.publicKey
+    );
+    const [issueStakerAccount] = await get_pda_from_seeds([
+      Buffer.from("issuestaker"),
+      issueAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+    const accounts = {
+      issueAccount,
+      repositoryAccount,
+      issueTokenPoolAccount,
+      issueStaker: repositoryCreator.publicKey,
+      issueStakerAccount,
+      issueStakerTokenAccount: repositoryCreatorTokenAccount,
+      rewardsMint: mintKeypair,
+      systemProgram: web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      pullRequestMetadataAccount: null,
+      issueStakerVerifiedUser: null,
+    };
+
+    //half the maximum lock-up earns half the maximum bonus
     await program.methods
-      .disperseGrant(new anchor.BN(10))
-      .accounts({
-        repositoryCreator: repositoryCreator.publicKey,
-        objective: objectiveAccount,
-        objectiveStakeAccount: objectiveStakeAccount,
-        repository: repositoryAccount,
-        issueAccount: issueAccount,
-        issueTokenPoolAccount: issueTokenPoolAccount,
-        systemProgram: web3.SystemProgram.programId,
-        tokenMint: mintKeypair,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-      })
+      .stakeIssue(new anchor.BN(10), new anchor.BN(15 * 24 * 60 * 60))
+      .accounts(accounts)
       .signers([repositoryCreator])
       .rpc(rpcConfig);
+
+    const issueStaker = await program.account.issueStaker.fetch(
+      issueStakerAccount
+    );
+    assert.equal(issueStaker.prVotingPower.toNumber(), 10 + 25 + 15);
+
+    try {
+      await program.methods
+        .unstakeIssue(new anchor.BN(10))
+        .accounts(accounts)
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+      assert.fail("locked stake was withdrawn");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "StakeLocked");
+    }
   });
-  it("Create an objective proposal", async () => {
-    let objectiveAccount = global.rootObjectiveAccount;
 
-    const proposee = await create_keypair();
-    const [objectiveProposal] = await get_pda_from_seeds([
-      Buffer.from("objective_proposal"),
-      objectiveAccount.toBuffer(),
-      Buffer.from(constant.proposalId),
-    ]);
-    await program.methods
-      .createObjectiveProposal(constant.proposalId, constant.proposalUri)
+  it("Creates a roadmap!", async () => {
+    let [
+      repositoryCreator,
+      repositoryAccount,
+      nameRouterAccount,
+      repositoryCreatorVerifiedAccount,
+      routerCreatorKeypair,
+    ] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.nameRouterAccount,
+      global.repositoryCreatorVerifiedAccount,
+      global.routerCreatorKeypair,
+    ];
+
+    const [metadataAccount] = await get_pda_from_seeds([
+      Buffer.from("roadmapmetadataadd"),
+      repositoryAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+
+    await program.methods
+      .addRoadmapData(
+        constant.roadmapTitle,
+        constant.roadmapDescription,
+        constant.roadmapImageUrl,
+        constant.roadmapOutlook
+      )
       .accounts({
-        proposee: proposee.publicKey,
-        objective: objectiveAccount,
+        nameRouterAccount,
+        metadataAccount,
+        roadmapDataAdder: repositoryCreator.publicKey,
+        roadmapVerifiedUser: repositoryCreatorVerifiedAccount,
+        routerCreator: routerCreatorKeypair.publicKey,
+        repositoryAccount: repositoryAccount,
         systemProgram: web3.SystemProgram.programId,
-        objectiveProposal: objectiveProposal,
       })
-      .signers([proposee])
+      .signers([repositoryCreator])
       .rpc(rpcConfig);
-    global.objectiveProposal = objectiveProposal;
+    global.roadmapMetadataAccount = metadataAccount;
   });
-  it("Vote on objective proposal", async () => {
-    let [objectiveAccount, objectiveProposal, granteeAccount, grantee] = [
-      global.rootObjectiveAccount,
-      global.objectiveProposal,
-      global.granteeAccount,
-      global.grantee,
+  it("Add an objective to a roadmap!", async () => {
+    let [
+      repositoryCreator,
+      repositoryAccount,
+      nameRouterAccount,
+      routerCreatorKeypair,
+      repositoryCreatorVerifiedAccount,
+      roadmapMetadataAccount,
+    ] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.nameRouterAccount,
+      global.routerCreatorKeypair,
+      global.repositoryCreatorVerifiedAccount,
+      global.roadmapMetadataAccount,
     ];
 
-    const [objectiveProposalVote] = await get_pda_from_seeds([
-      objectiveProposal.toBuffer(),
-      objectiveAccount.toBuffer(),
-      grantee.publicKey.toBuffer(),
+    const [objectiveAccount] = await get_pda_from_seeds([
+      Buffer.from("objectivedataadd"),
+      repositoryCreator.publicKey.toBuffer(),
+      Buffer.from(constant.objectiveId),
     ]);
 
     await program.methods
-      .voteObjective(true)
+      .addObjectiveData(
+        constant.objectiveId,
+        constant.objectiveTitle,
+        constant.objectiveStartUnix,
+        constant.objectiveDescription,
+        constant.objectiveDeliverable
+      )
       .accounts({
-        voter: grantee.publicKey,
-        objective: objectiveAccount,
-        grantAccount: granteeAccount,
-        proposal: objectiveProposal,
-        objectiveProposalVote: objectiveProposalVote,
+        nameRouterAccount,
+        metadataAccount: objectiveAccount,
+        roadmapMetadataAccount: roadmapMetadataAccount,
+        parentObjectiveAccount: null,
+        objectiveDataAddr: repositoryCreator.publicKey,
+        objectiveVerifiedUser: repositoryCreatorVerifiedAccount,
+        routerCreator: routerCreatorKeypair.publicKey,
+        repositoryAccount: repositoryAccount,
         systemProgram: web3.SystemProgram.programId,
       })
-      .signers([grantee])
+      .signers([repositoryCreator])
       .rpc(rpcConfig);
+    global.rootObjectiveAccount = objectiveAccount;
   });
-  it("Change repo token", async () => {
+  it("Add a child objective to an objective", async () => {
     let [
       repositoryCreator,
+      repositoryAccount,
       nameRouterAccount,
-      repositoryCreatorVerifiedAccount,
       routerCreatorKeypair,
+      repositoryCreatorVerifiedAccount,
+      objectiveAccount,
     ] = [
       global.repositoryCreator,
+      global.repositoryAccount,
       global.nameRouterAccount,
+      global.routerCreatorKeypair,
       global.repositoryCreatorVerifiedAccount,
+      global.rootObjectiveAccount,
+    ];
+
+    const [objectiveAccount2] = await get_pda_from_seeds([
+      Buffer.from("objectivedataadd"),
+      repositoryCreator.publicKey.toBuffer(),
+      Buffer.from("2"),
+    ]);
+    await program.methods
+      .addObjectiveData(
+        "2",
+        constant.objectiveTitle,
+        constant.objectiveStartUnix,
+        constant.objectiveDescription,
+        constant.objectiveDeliverable
+      )
+      .accounts({
+        nameRouterAccount,
+        metadataAccount: objectiveAccount2,
+        objectiveDataAddr: repositoryCreator.publicKey,
+        roadmapMetadataAccount: null,
+        parentObjectiveAccount: objectiveAccount,
+        objectiveVerifiedUser: repositoryCreatorVerifiedAccount,
+        repositoryAccount: repositoryAccount,
+        routerCreator: routerCreatorKeypair.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+  });
+  it("Adds a PR to an issue", async () => {
+    let [
+      repositoryAccount,
+      mintKeypair,
+      issueAccount,
+      routerCreatorKeypair,
+      nameRouterAccount,
+    ] = [
+      global.repositoryAccount,
+      global.mintKeypair,
+      global.issueAccount,
       global.routerCreatorKeypair,
+      global.nameRouterAccount,
     ];
 
-    const thirdId = constant.repositoryId + "3";
-    const importedMint = await createMint(
-      connection,
-      repositoryCreator,
-      repositoryCreator.publicKey,
-      repositoryCreator.publicKey,
-      3
+    const pullRequestCreator = await create_keypair();
+    const [pullRequestCreatorVerifiedAccount] = await create_verified_user(
+      routerCreatorKeypair,
+      nameRouterAccount,
+      pullRequestCreator.publicKey
     );
 
-    const [repositoryAccount] = await get_pda_from_seeds([
-      Buffer.from("repository"),
-      Buffer.from(thirdId),
-      repositoryCreator.publicKey.toBuffer(),
+    const [pullRequestMetadataAccount] = await get_pda_from_seeds([
+      Buffer.from("pullrequestadded"),
+      issueAccount.toBuffer(),
+      pullRequestCreator.publicKey.toBuffer(),
     ]);
 
-    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
-      importedMint,
-      repositoryCreator.publicKey
+    const pullRequestTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      pullRequestMetadataAccount,
+      true
+    );
+
+    await program.methods
+      .addPr(constant.pullRequestMetadataUri)
+      .accounts({
+        pullRequestVerifiedUser: pullRequestCreatorVerifiedAccount,
+        issue: issueAccount,
+        pullRequestMetadataAccount: pullRequestMetadataAccount,
+        nameRouterAccount,
+        pullRequestTokenAccount,
+        pullRequestAddr: pullRequestCreator.publicKey,
+        repositoryAccount,
+        routerCreator: routerCreatorKeypair.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rewardsMint: mintKeypair,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([pullRequestCreator])
+      .rpc(rpcConfig);
+    global.pullRequestCreator = pullRequestCreator;
+    global.pullRequestCreatorVerifiedAccount =
+      pullRequestCreatorVerifiedAccount;
+    global.pullRequestMetadataAccount = pullRequestMetadataAccount;
+  });
+  it("Accepts a PR", async () => {
+    let [
+      repositoryCreator,
+      repositoryAccount,
+      pullRequestMetadataAccount,
+      pullRequestCreator,
+      issueAccount,
+    ] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.pullRequestMetadataAccount,
+      global.pullRequestCreator,
+      global.issueAccount,
+    ];
+
+    await program.methods
+      .acceptPr(constant.repositoryId)
+      .accounts({
+        pullRequestAddr: pullRequestCreator.publicKey,
+        pullRequestMetadataAccount,
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryAccount,
+        issue: issueAccount,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+  });
+
+  it("Getting a PR accepted and getting rewarded for it", async () => {
+    let [
+      repositoryCreator,
+      repositoryAccount,
+      pullRequestMetadataAccount,
+      pullRequestCreator,
+      issueAccount,
+      mintKeypair,
+      issueCreator,
+    ] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.pullRequestMetadataAccount,
+      global.pullRequestCreator,
+      global.issueAccount,
+      global.mintKeypair,
+      global.issueCreator,
+    ];
+
+    const pullRequestCreatorRewardAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      pullRequestCreator.publicKey
+    );
+
+    const issueTokenPoolAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      issueAccount,
+      true
     );
 
-    //creating a new repo as cant change repo token of repo with existing issues
     await program.methods
-      .createRepository(
-        thirdId,
-        constant.repositoryTitle,
-        constant.repositoryUri,
-        constant.tokenName,
-        constant.tokenimage,
-        constant.tokenMetadata
-      )
+      .setRewardCuts(2000, 1000)
+      .accounts({
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryAccount,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+    //an already posted issue keeps the cuts it was created with
+    const issue = await program.account.issue.fetch(issueAccount);
+    assert.equal(issue.maintainerCutBps, 1000);
+    assert.equal(issue.issueReporterCutBps, 500);
+
+    //reward accounts that don't exist yet are created by the claim
+    const rewardBalance = async (owner) => {
+      try {
+        const balance = await connection.getTokenAccountBalance(
+          await getAssociatedTokenAddress(mintKeypair, owner)
+        );
+        return Number(balance.value.amount);
+      } catch (err) {
+        return 0;
+      }
+    };
+    const recipients = [
+      pullRequestCreator.publicKey,
+      repositoryCreator.publicKey,
+      issueCreator.publicKey,
+    ];
+    const balancesBefore = await Promise.all(recipients.map(rewardBalance));
+    const issueReward = Number(
+      (await connection.getTokenAccountBalance(issueTokenPoolAccount)).value
+        .amount
+    );
+    assert.isAbove(issueReward, 0);
+
+    const bountyRewardAccount = async (owner) => ({
+      pubkey: await getAssociatedTokenAddress(global.bountyMint, owner),
+      isWritable: true,
+      isSigner: false,
+    });
+
+    await program.methods
+      .claimReward()
+      .accounts({
+        pullRequestCreator: pullRequestCreator.publicKey,
+        pullRequest: pullRequestMetadataAccount,
+        pullRequestCreatorRewardAccount,
+        repositoryCreatorRewardAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          repositoryCreator.publicKey
+        ),
+        issueCreatorRewardAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          issueCreator.publicKey
+        ),
+        repositoryCreator: repositoryCreator.publicKey,
+        rewardsMint: mintKeypair,
+        repositoryAccount,
+        issueAccount: issueAccount,
+        issueTokenPoolAccount,
+        issueCreator: issueCreator.publicKey,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      //pays out the bounty pool staked in a second mint as well
+      .remainingAccounts([
+        { pubkey: global.bountyMint, isWritable: false, isSigner: false },
+        {
+          pubkey: await getAssociatedTokenAddress(
+            global.bountyMint,
+            issueAccount,
+            true
+          ),
+          isWritable: true,
+          isSigner: false,
+        },
+        await bountyRewardAccount(pullRequestCreator.publicKey),
+        await bountyRewardAccount(repositoryCreator.publicKey),
+        await bountyRewardAccount(issueCreator.publicKey),
+        { pubkey: TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
+      ])
+      .signers([pullRequestCreator])
+      .rpc(rpcConfig);
+
+    //the contributor gets 85% once the maintainer's 10% and the issue reporter's 5% are taken
+    const maintainerAmount = Math.floor((issueReward * 1000) / 10000);
+    const issueReporterAmount = Math.floor((issueReward * 500) / 10000);
+    const balancesAfter = await Promise.all(recipients.map(rewardBalance));
+    assert.deepEqual(
+      balancesAfter.map((balance, i) => balance - balancesBefore[i]),
+      [
+        issueReward - maintainerAmount - issueReporterAmount,
+        maintainerAmount,
+        issueReporterAmount,
+      ]
+    );
+  });
+
+  it("Splits an issue reward between a PR author and a co-author", async () => {
+    let [
+      repositoryCreator,
+      repositoryAccount,
+      mintKeypair,
+      issueCreator,
+      issueVerifiedUser,
+      pullRequestCreator,
+      pullRequestCreatorVerifiedAccount,
+    ] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.mintKeypair,
+      global.issueCreator,
+      global.issueVerifiedUser,
+      global.pullRequestCreator,
+      global.pullRequestCreatorVerifiedAccount,
+    ];
+    const coAuthor = await create_keypair();
+
+    const { issueIndex } = await program.account.repository.fetch(
+      repositoryAccount
+    );
+    const [issueAccount] = await get_pda_from_seeds([
+      Buffer.from("issue"),
+      Buffer.from(issueIndex.toString()),
+      repositoryAccount.toBuffer(),
+      issueCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .addIssue(constant.repositoryUri, null, [], null, null)
+      .accounts({
+        issueAccount,
+        issueCreator: issueCreator.publicKey,
+        issueVerifiedUser,
+        repositoryAccount,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([issueCreator])
+      .rpc(rpcConfig);
+    const postedIssue = await program.account.issue.fetch(issueAccount);
+    assert.equal(postedIssue.maintainerCutBps, 2000);
+    assert.equal(postedIssue.issueReporterCutBps, 1000);
+
+    const issueTokenPoolAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      issueAccount,
+      true
+    );
+    const [issueStakerAccount] = await get_pda_from_seeds([
+      Buffer.from("issuestaker"),
+      issueAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .stakeIssue(new anchor.BN(10), new anchor.BN(0))
+      .accounts({
+        issueAccount,
+        repositoryAccount,
+        issueTokenPoolAccount,
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount,
+        issueStakerTokenAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          repositoryCreator.publicKey
+        ),
+        rewardsMint: mintKeypair,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        pullRequestMetadataAccount: null,
+        issueStakerVerifiedUser: null,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const [pullRequestMetadataAccount] = await get_pda_from_seeds([
+      Buffer.from("pullrequestadded"),
+      issueAccount.toBuffer(),
+      pullRequestCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .addPr(constant.pullRequestMetadataUri)
+      .accounts({
+        pullRequestVerifiedUser: pullRequestCreatorVerifiedAccount,
+        issue: issueAccount,
+        pullRequestMetadataAccount,
+        pullRequestAddr: pullRequestCreator.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([pullRequestCreator])
+      .rpc(rpcConfig);
+
+    await program.methods
+      .acceptRewardSplit([
+        { recipient: pullRequestCreator.publicKey, shareBps: 6000 },
+        { recipient: coAuthor.publicKey, shareBps: 4000 },
+      ])
+      .accounts({
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryAccount,
+        issueAccount,
+      })
+      .remainingAccounts([
+        {
+          pubkey: pullRequestMetadataAccount,
+          isWritable: true,
+          isSigner: false,
+        },
+      ])
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const claim = async (claimer, pullRequest, poolAccount) =>
+      program.methods
+        .claimReward()
+        .accounts({
+          pullRequestCreator: claimer.publicKey,
+          pullRequest,
+          pullRequestCreatorRewardAccount: await getAssociatedTokenAddress(
+            mintKeypair,
+            claimer.publicKey
+          ),
+          repositoryCreatorRewardAccount: await getAssociatedTokenAddress(
+            mintKeypair,
+            repositoryCreator.publicKey
+          ),
+          issueCreatorRewardAccount: await getAssociatedTokenAddress(
+            mintKeypair,
+            issueCreator.publicKey
+          ),
+          repositoryCreator: repositoryCreator.publicKey,
+          rewardsMint: mintKeypair,
+          repositoryAccount,
+          issueAccount,
+          issueTokenPoolAccount: poolAccount,
+          issueCreator: issueCreator.publicKey,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([claimer])
+        .rpc(rpcConfig);
+
+    //a recipient can't leave out the staked issue tokens and still settle their share
+    try {
+      await claim(coAuthor, null, null);
+      assert.fail("claimed a staked issue without its token pool");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "MissingIssueTokenPool");
+    }
+
+    //each recipient claims their own share, the co-author has no PR of their own
+    await claim(
+      pullRequestCreator,
+      pullRequestMetadataAccount,
+      issueTokenPoolAccount
+    );
+    await claim(coAuthor, null, issueTokenPoolAccount);
+
+    const coAuthorReward = await connection.getTokenAccountBalance(
+      await getAssociatedTokenAddress(mintKeypair, coAuthor.publicKey)
+    );
+    assert.equal(coAuthorReward.value.amount, "4");
+  });
+
+  it("Expires an issue past its deadline and refunds stakers", async () => {
+    let [
+      repositoryCreator,
+      repositoryAccount,
+      mintKeypair,
+      issueCreator,
+      issueVerifiedUser,
+    ] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.mintKeypair,
+      global.issueCreator,
+      global.issueVerifiedUser,
+    ];
+
+    const { issueIndex } = await program.account.repository.fetch(
+      repositoryAccount
+    );
+    const [issueAccount] = await get_pda_from_seeds([
+      Buffer.from("issue"),
+      Buffer.from(issueIndex.toString()),
+      repositoryAccount.toBuffer(),
+      issueCreator.publicKey.toBuffer(),
+    ]);
+    const deadline = Math.floor(Date.now() / 1000) + 2;
+    await program.methods
+      .addIssue(
+        constant.repositoryUri,
+        new anchor.BN(deadline),
+        [],
+        null,
+        null
+      )
+      .accounts({
+        issueAccount,
+        issueCreator: issueCreator.publicKey,
+        issueVerifiedUser,
+        repositoryAccount,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([issueCreator])
+      .rpc(rpcConfig);
+
+    const issueTokenPoolAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      issueAccount,
+      true
+    );
+    const [issueStakerAccount] = await get_pda_from_seeds([
+      Buffer.from("issuestaker"),
+      issueAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+    const stakeAccounts = {
+      issueAccount,
+      repositoryAccount,
+      issueTokenPoolAccount,
+      issueStaker: repositoryCreator.publicKey,
+      issueStakerAccount,
+      issueStakerTokenAccount: await getAssociatedTokenAddress(
+        mintKeypair,
+        repositoryCreator.publicKey
+      ),
+      rewardsMint: mintKeypair,
+      systemProgram: web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      pullRequestMetadataAccount: null,
+      issueStakerVerifiedUser: null,
+    };
+
+    //a locked stake is still refunded in full once the issue expires
+    await program.methods
+      .stakeIssue(new anchor.BN(10), new anchor.BN(60 * 60))
+      .accounts(stakeAccounts)
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    await delay(4000);
+    await program.methods
+      .expireIssue()
+      .accounts({
+        initiator: repositoryCreator.publicKey,
+        repositoryAccount,
+        issueAccount,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    await program.methods
+      .unstakeIssue(new anchor.BN(10))
+      .accounts(stakeAccounts)
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    assert.isNull(await connection.getAccountInfo(issueStakerAccount));
+  });
+
+  it("Cancels an issue and refunds stakers", async () => {
+    let [
+      repositoryCreator,
+      repositoryAccount,
+      mintKeypair,
+      issueCreator,
+      issueVerifiedUser,
+    ] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.mintKeypair,
+      global.issueCreator,
+      global.issueVerifiedUser,
+    ];
+
+    const { issueIndex } = await program.account.repository.fetch(
+      repositoryAccount
+    );
+    const [issueAccount] = await get_pda_from_seeds([
+      Buffer.from("issue"),
+      Buffer.from(issueIndex.toString()),
+      repositoryAccount.toBuffer(),
+      issueCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .addIssue(constant.repositoryUri, null, [], null, null)
+      .accounts({
+        issueAccount,
+        issueCreator: issueCreator.publicKey,
+        issueVerifiedUser,
+        repositoryAccount,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([issueCreator])
+      .rpc(rpcConfig);
+
+    const [issueStakerAccount] = await get_pda_from_seeds([
+      Buffer.from("issuestaker"),
+      issueAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+    const stakeAccounts = {
+      issueAccount,
+      repositoryAccount,
+      issueTokenPoolAccount: await getAssociatedTokenAddress(
+        mintKeypair,
+        issueAccount,
+        true
+      ),
+      issueStaker: repositoryCreator.publicKey,
+      issueStakerAccount,
+      issueStakerTokenAccount: await getAssociatedTokenAddress(
+        mintKeypair,
+        repositoryCreator.publicKey
+      ),
+      rewardsMint: mintKeypair,
+      systemProgram: web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      pullRequestMetadataAccount: null,
+      issueStakerVerifiedUser: null,
+    };
+
+    await program.methods
+      .stakeIssue(new anchor.BN(10), new anchor.BN(0))
+      .accounts(stakeAccounts)
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    await program.methods
+      .cancelIssue()
+      .accounts({
+        authority: issueCreator.publicKey,
+        repositoryAccount,
+        issueAccount,
+      })
+      .signers([issueCreator])
+      .rpc(rpcConfig);
+
+    try {
+      await program.methods
+        .stakeIssue(new anchor.BN(10), new anchor.BN(0))
+        .accounts(stakeAccounts)
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+      assert.fail("staked on a cancelled issue");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "IssueClosedAlready");
+    }
+
+    await program.methods
+      .unstakeIssue(new anchor.BN(10))
+      .accounts(stakeAccounts)
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+  });
+
+  it("Create communal account to store tokens", async () => {
+    let [repositoryCreator, mintKeypair] = [
+      global.repositoryCreator,
+      global.mintKeypair,
+    ];
+
+    const [communal_account] = await get_pda_from_seeds([
+      Buffer.from("are_we_conscious"),
+      Buffer.from("is love life ?  "),
+      Buffer.from("arewemadorinlove"),
+      mintKeypair.toBuffer(),
+    ]);
+
+    const communalTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      communal_account,
+      true
+    );
+
+    const [repositoryTreasury] = await get_pda_from_seeds([
+      Buffer.from("treasury"),
+      global.repositoryAccount.toBuffer(),
+    ]);
+    const [protocolTreasury] = await get_pda_from_seeds([
+      Buffer.from("treasury"),
+      Buffer.from("protocol"),
+    ]);
+
+    await program.methods
+      .createCommunalAccount()
+      .accounts({
+        programConfig: global.programConfig,
+        authority: repositoryCreator.publicKey,
+        communalDeposit: communal_account,
+        communalTokenAccount: communalTokenAccount,
+        systemProgram: web3.SystemProgram.programId,
+        rewardsMint: mintKeypair,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        usdcTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        usdcMint: mintKeypair,
+        communalUsdcAccount: communalTokenAccount,
+        repositoryAccount: global.repositoryAccount,
+        repositoryTreasury,
+        repositoryTreasuryUsdcAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          repositoryTreasury,
+          true
+        ),
+        protocolTreasury,
+        protocolTreasuryUsdcAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          protocolTreasury,
+          true
+        ),
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+    global.communalAccount = communal_account;
+    global.repositoryTreasury = repositoryTreasury;
+    global.protocolTreasury = protocolTreasury;
+  });
+
+  it("Sends a buy transaction", async () => {
+    let [repositoryCreator, mintKeypair, communalAccount, repositoryAccount] = [
+      global.repositoryCreator,
+      global.mintKeypair,
+      global.communalAccount,
+      global.repositoryAccount,
+    ];
+
+    const communalTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      communalAccount,
+      true
+    );
+
+    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryCreator.publicKey
+    );
+
+    //the plain instruction takes the exact curve price for a whole token
+    const { usdcAmount } = await program.methods
+      .quoteBuy(new anchor.BN(10))
+      .accounts({
+        repositoryAccount,
+        rewardsMint: mintKeypair,
+        programConfig: global.programConfig,
+        usdcMint: mintKeypair,
+      })
+      .view();
+    await program.methods
+      .buyTokens(usdcAmount, new anchor.BN(10))
+      .accounts({
+        programConfig: global.programConfig,
+        buyer: repositoryCreator.publicKey,
+        communalDeposit: communalAccount,
+        communalTokenAccount: communalTokenAccount,
+        rewardsMint: mintKeypair,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        usdcTokenProgram: TOKEN_PROGRAM_ID,
+        repositoryAccount: repositoryAccount,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+        buyerTokenAccount: repositoryCreatorTokenAccount,
+        communalUsdcAccount: communalTokenAccount,
+        buyerUsdcAccount: repositoryCreatorTokenAccount,
+        usdcMint: mintKeypair,
+        repositoryTreasury: global.repositoryTreasury,
+        repositoryTreasuryUsdcAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          global.repositoryTreasury,
+          true
+        ),
+        protocolTreasury: global.protocolTreasury,
+        protocolTreasuryUsdcAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          global.protocolTreasury,
+          true
+        ),
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+  });
+
+  it("Sends a sell transaction", async () => {
+    let [repositoryCreator, mintKeypair, communalAccount, repositoryAccount] = [
+      global.repositoryCreator,
+      global.mintKeypair,
+      global.communalAccount,
+      global.repositoryAccount,
+    ];
+
+    const communalTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      communalAccount,
+      true
+    );
+
+    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryCreator.publicKey
+    );
+
+    const { usdcAmount } = await program.methods
+      .quoteSell(new anchor.BN(10))
+      .accounts({
+        repositoryAccount,
+        rewardsMint: mintKeypair,
+        programConfig: global.programConfig,
+        usdcMint: mintKeypair,
+      })
+      .view();
+    await program.methods
+      .sellTokens(usdcAmount, new anchor.BN(10))
+      .accounts({
+        programConfig: global.programConfig,
+        seller: repositoryCreator.publicKey,
+        communalDeposit: communalAccount,
+        communalTokenAccount: communalTokenAccount,
+        rewardsMint: mintKeypair,
+        repositoryAccount: repositoryAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        usdcTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+        sellerTokenAccount: repositoryCreatorTokenAccount,
+        communalUsdcAccount: communalTokenAccount,
+        sellerUsdcAccount: repositoryCreatorTokenAccount,
+        usdcMint: mintKeypair,
+        repositoryTreasury: global.repositoryTreasury,
+        repositoryTreasuryUsdcAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          global.repositoryTreasury,
+          true
+        ),
+        protocolTreasury: global.protocolTreasury,
+        protocolTreasuryUsdcAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          global.protocolTreasury,
+          true
+        ),
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+  });
+
+  it("Sends slippage bounded buy and sell transactions", async () => {
+    let [repositoryCreator, mintKeypair, communalAccount, repositoryAccount] = [
+      global.repositoryCreator,
+      global.mintKeypair,
+      global.communalAccount,
+      global.repositoryAccount,
+    ];
+
+    const communalTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      communalAccount,
+      true
+    );
+
+    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryCreator.publicKey
+    );
+
+    const tradeAccounts = {
+      programConfig: global.programConfig,
+      communalDeposit: communalAccount,
+      communalTokenAccount: communalTokenAccount,
+      rewardsMint: mintKeypair,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      usdcTokenProgram: TOKEN_PROGRAM_ID,
+      repositoryAccount: repositoryAccount,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: web3.SystemProgram.programId,
+      communalUsdcAccount: communalTokenAccount,
+      usdcMint: mintKeypair,
+      repositoryTreasury: global.repositoryTreasury,
+      repositoryTreasuryUsdcAccount: await getAssociatedTokenAddress(
+        mintKeypair,
+        global.repositoryTreasury,
+        true
+      ),
+      protocolTreasury: global.protocolTreasury,
+      protocolTreasuryUsdcAccount: await getAssociatedTokenAddress(
+        mintKeypair,
+        global.protocolTreasury,
+        true
+      ),
+    };
+    const buyAccounts = {
+      ...tradeAccounts,
+      buyer: repositoryCreator.publicKey,
+      buyerTokenAccount: repositoryCreatorTokenAccount,
+      buyerUsdcAccount: repositoryCreatorTokenAccount,
+    };
+    const sellAccounts = {
+      ...tradeAccounts,
+      seller: repositoryCreator.publicKey,
+      sellerTokenAccount: repositoryCreatorTokenAccount,
+      sellerUsdcAccount: repositoryCreatorTokenAccount,
+    };
+    const quoteAccounts = {
+      repositoryAccount,
+      rewardsMint: mintKeypair,
+      programConfig: global.programConfig,
+      usdcMint: mintKeypair,
+    };
+
+    //a fraction of a token would be priced at nothing
+    try {
+      await program.methods
+        .buyTokensWithSlippage(new anchor.BN(1), new anchor.BN(1))
+        .accounts(buyAccounts)
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+      assert.fail("bought a fraction of a token");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "FractionalTokenAmount");
+    }
+    try {
+      await program.methods
+        .sellTokensWithSlippage(new anchor.BN(1), new anchor.BN(0))
+        .accounts(sellAccounts)
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+      assert.fail("sold a fraction of a token");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "FractionalTokenAmount");
+    }
+
+    //one whole token at exactly the quoted price each way
+    const buyQuote = await program.methods
+      .quoteBuy(new anchor.BN(10))
+      .accounts(quoteAccounts)
+      .view();
+    await program.methods
+      .buyTokensWithSlippage(new anchor.BN(10), buyQuote.totalUsdc)
+      .accounts(buyAccounts)
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const sellQuote = await program.methods
+      .quoteSell(new anchor.BN(10))
+      .accounts(quoteAccounts)
+      .view();
+    await program.methods
+      .sellTokensWithSlippage(new anchor.BN(10), sellQuote.totalUsdc)
+      .accounts(sellAccounts)
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+  });
+
+  it("Swaps repository tokens across two bonding curves", async () => {
+    let [
+      repositoryCreator,
+      mintKeypair,
+      communalAccount,
+      repositoryAccount,
+      linearRepositoryAccount,
+      linearMintKeypair,
+    ] = [
+      global.repositoryCreator,
+      global.mintKeypair,
+      global.communalAccount,
+      global.repositoryAccount,
+      global.linearRepositoryAccount,
+      global.linearMintKeypair,
+    ];
+
+    const communalTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      communalAccount,
+      true
+    );
+    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryCreator.publicKey
+    );
+    const repositoryTreasuryUsdcAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      global.repositoryTreasury,
+      true
+    );
+    const protocolTreasuryUsdcAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      global.protocolTreasury,
+      true
+    );
+
+    //registers the communal account and treasury of the second repository
+    const [linearCommunalAccount] = await get_pda_from_seeds([
+      Buffer.from("are_we_conscious"),
+      Buffer.from("is love life ?  "),
+      Buffer.from("arewemadorinlove"),
+      linearMintKeypair.toBuffer(),
+    ]);
+    const linearCommunalTokenAccount = await getAssociatedTokenAddress(
+      linearMintKeypair,
+      linearCommunalAccount,
+      true
+    );
+    const linearCommunalUsdcAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      linearCommunalAccount,
+      true
+    );
+    const [linearRepositoryTreasury] = await get_pda_from_seeds([
+      Buffer.from("treasury"),
+      linearRepositoryAccount.toBuffer(),
+    ]);
+    const linearRepositoryTreasuryUsdcAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      linearRepositoryTreasury,
+      true
+    );
+
+    await program.methods
+      .createCommunalAccount()
+      .accounts({
+        programConfig: global.programConfig,
+        authority: repositoryCreator.publicKey,
+        communalDeposit: linearCommunalAccount,
+        communalTokenAccount: linearCommunalTokenAccount,
+        systemProgram: web3.SystemProgram.programId,
+        rewardsMint: linearMintKeypair,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        usdcTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        usdcMint: mintKeypair,
+        communalUsdcAccount: linearCommunalUsdcAccount,
+        repositoryAccount: linearRepositoryAccount,
+        repositoryTreasury: linearRepositoryTreasury,
+        repositoryTreasuryUsdcAccount: linearRepositoryTreasuryUsdcAccount,
+        protocolTreasury: global.protocolTreasury,
+        protocolTreasuryUsdcAccount,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    //buys whole tokens on the first curve so there is supply to swap out of
+    await program.methods
+      .buyTokensWithSlippage(new anchor.BN(100), new anchor.BN(1000))
+      .accounts({
+        programConfig: global.programConfig,
+        buyer: repositoryCreator.publicKey,
+        communalDeposit: communalAccount,
+        communalTokenAccount: communalTokenAccount,
+        rewardsMint: mintKeypair,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        usdcTokenProgram: TOKEN_PROGRAM_ID,
+        repositoryAccount: repositoryAccount,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+        buyerTokenAccount: repositoryCreatorTokenAccount,
+        communalUsdcAccount: communalTokenAccount,
+        buyerUsdcAccount: repositoryCreatorTokenAccount,
+        usdcMint: mintKeypair,
+        repositoryTreasury: global.repositoryTreasury,
+        repositoryTreasuryUsdcAccount,
+        protocolTreasury: global.protocolTreasury,
+        protocolTreasuryUsdcAccount,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    await program.methods
+      .swapTokens(new anchor.BN(20), new anchor.BN(1))
+      .accounts({
+        swapper: repositoryCreator.publicKey,
+        swapperTokenInAccount: repositoryCreatorTokenAccount,
+        swapperTokenOutAccount: await getAssociatedTokenAddress(
+          linearMintKeypair,
+          repositoryCreator.publicKey
+        ),
+        repositoryIn: repositoryAccount,
+        repositoryOut: linearRepositoryAccount,
+        rewardsMintIn: mintKeypair,
+        rewardsMintOut: linearMintKeypair,
+        communalDepositIn: communalAccount,
+        communalTokenInAccount: communalTokenAccount,
+        communalUsdcInAccount: communalTokenAccount,
+        communalDepositOut: linearCommunalAccount,
+        communalUsdcOutAccount: linearCommunalUsdcAccount,
+        usdcMint: mintKeypair,
+        repositoryInTreasury: global.repositoryTreasury,
+        repositoryInTreasuryUsdcAccount: repositoryTreasuryUsdcAccount,
+        repositoryOutTreasury: linearRepositoryTreasury,
+        repositoryOutTreasuryUsdcAccount: linearRepositoryTreasuryUsdcAccount,
+        protocolTreasury: global.protocolTreasury,
+        protocolTreasuryUsdcAccount,
+        programConfig: global.programConfig,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        usdcTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+    global.linearCommunalAccount = linearCommunalAccount;
+    global.linearRepositoryTreasury = linearRepositoryTreasury;
+  });
+
+  it("Migrates a legacy repository and trades on it", async () => {
+    let [repositoryCreator, mintKeypair] = [
+      global.repositoryCreator,
+      global.mintKeypair,
+    ];
+    //loaded into the validator from tests/DefiOS/fixtures
+    const repositoryAccount = new web3.PublicKey(
+      "5s2EFrHarc3XYenVxJzzsAKUbeR9g4YMAxYT64bsnGt2"
+    );
+    const legacyMint = new web3.PublicKey(
+      "8h6QDbpnFX2mtGrYDjxue1ftVy3MpZGKsQspDv1EDtU2"
+    );
+
+    await program.methods
+      .migrateRepository()
+      .accounts({
+        payer: repositoryCreator.publicKey,
+        repositoryAccount,
+        programConfig: global.programConfig,
+        rewardsMint: legacyMint,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    //the legacy carve-out was minted in raw units at the mint's decimals
+    const repository = await program.account.repository.fetch(
+      repositoryAccount
+    );
+    assert.deepEqual(repository.curveConfig.curveType, { linear: {} });
+    assert.equal(repository.curveConfig.slope.toNumber(), 2);
+    assert.equal(repository.curveConfig.basePrice.toNumber(), 0);
+    assert.equal(repository.vestingAllocation.toNumber(), 100000);
+
+    const [communalAccount] = await get_pda_from_seeds([
+      Buffer.from("are_we_conscious"),
+      Buffer.from("is love life ?  "),
+      Buffer.from("arewemadorinlove"),
+      legacyMint.toBuffer(),
+    ]);
+    const [repositoryTreasury] = await get_pda_from_seeds([
+      Buffer.from("treasury"),
+      repositoryAccount.toBuffer(),
+    ]);
+    const tradeAccounts = {
+      programConfig: global.programConfig,
+      communalDeposit: communalAccount,
+      communalTokenAccount: await getAssociatedTokenAddress(
+        legacyMint,
+        communalAccount,
+        true
+      ),
+      rewardsMint: legacyMint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      usdcTokenProgram: TOKEN_PROGRAM_ID,
+      repositoryAccount,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: web3.SystemProgram.programId,
+      communalUsdcAccount: await getAssociatedTokenAddress(
+        mintKeypair,
+        communalAccount,
+        true
+      ),
+      usdcMint: mintKeypair,
+      repositoryTreasury,
+      repositoryTreasuryUsdcAccount: await getAssociatedTokenAddress(
+        mintKeypair,
+        repositoryTreasury,
+        true
+      ),
+      protocolTreasury: global.protocolTreasury,
+      protocolTreasuryUsdcAccount: await getAssociatedTokenAddress(
+        mintKeypair,
+        global.protocolTreasury,
+        true
+      ),
+    };
+
+    await program.methods
+      .createCommunalAccount()
+      .accounts({ ...tradeAccounts, authority: repositoryCreator.publicKey })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const quoteAccounts = {
+      repositoryAccount,
+      rewardsMint: legacyMint,
+      programConfig: global.programConfig,
+      usdcMint: mintKeypair,
+    };
+    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
+      legacyMint,
+      repositoryCreator.publicKey
+    );
+    const repositoryCreatorUsdcAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryCreator.publicKey
+    );
+
+    //nothing has been bought off the curve yet, so the first token costs 2 * 1^2 / 2
+    const buyQuote = await program.methods
+      .quoteBuy(new anchor.BN(10))
+      .accounts(quoteAccounts)
+      .view();
+    assert.equal(buyQuote.usdcAmount.toNumber(), 1);
+    await program.methods
+      .buyTokensWithSlippage(new anchor.BN(10), buyQuote.totalUsdc)
+      .accounts({
+        ...tradeAccounts,
+        buyer: repositoryCreator.publicKey,
+        buyerTokenAccount: repositoryCreatorTokenAccount,
+        buyerUsdcAccount: repositoryCreatorUsdcAccount,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const sellQuote = await program.methods
+      .quoteSell(new anchor.BN(10))
+      .accounts(quoteAccounts)
+      .view();
+    assert.equal(sellQuote.usdcAmount.toNumber(), 1);
+    await program.methods
+      .sellTokensWithSlippage(new anchor.BN(10), sellQuote.totalUsdc)
+      .accounts({
+        ...tradeAccounts,
+        seller: repositoryCreator.publicKey,
+        sellerTokenAccount: repositoryCreatorTokenAccount,
+        sellerUsdcAccount: repositoryCreatorUsdcAccount,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const reserve = await connection.getTokenAccountBalance(
+      tradeAccounts.communalUsdcAccount
+    );
+    assert.equal(reserve.value.amount, "0");
+  });
+
+  it("Re-points the vesting beneficiary and revokes unvested tokens", async () => {
+    let [repositoryCreator, repositoryAccount, mintKeypair, vestingAccount] = [
+      global.repositoryCreator,
+      global.linearRepositoryAccount,
+      global.linearMintKeypair,
+      global.linearVestingAccount,
+    ];
+    const newBeneficiary = await create_keypair();
+
+    const newBeneficiaryTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      newBeneficiary.publicKey
+    );
+
+    await program.methods
+      .changeVestingBeneficiary()
+      .accounts({
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryAccount,
+        vestingAccount,
+        tokenMint: mintKeypair,
+        newBeneficiary: newBeneficiary.publicKey,
+        newBeneficiaryTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const vestingTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      vestingAccount,
+      true
+    );
+    const supplyBefore = Number(
+      (await connection.getTokenSupply(mintKeypair)).value.amount
+    );
+    const vestedBefore = Number(
+      (await connection.getTokenAccountBalance(vestingTokenAccount)).value
+        .amount
+    );
+
+    await program.methods
+      .revokeVesting()
+      .accounts({
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryAccount,
+        vestingAccount,
+        vestingTokenAccount,
+        tokenMint: mintKeypair,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    //unvested tokens are burnt instead of landing anywhere withdrawable
+    const revoked =
+      vestedBefore -
+      Number(
+        (await connection.getTokenAccountBalance(vestingTokenAccount)).value
+          .amount
+      );
+    assert.isAbove(revoked, 0);
+    assert.equal(
+      Number((await connection.getTokenSupply(mintKeypair)).value.amount),
+      supplyBefore - revoked
+    );
+
+    //the new beneficiary unlocks what had vested without the founder signing
+    await program.methods
+      .unlockTokens()
+      .accounts({
+        repositoryAccount,
+        beneficiaryTokenAccount: newBeneficiaryTokenAccount,
+        beneficiary: newBeneficiary.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+        vestingAccount,
+        tokenMint: mintKeypair,
+        vestingTokenAccount,
+      })
+      .signers([newBeneficiary])
+      .rpc(rpcConfig);
+    assert.isAbove(
+      Number(
+        (await connection.getTokenAccountBalance(newBeneficiaryTokenAccount))
+          .value.amount
+      ),
+      0
+    );
+
+    //the burnt tokens leave both the supply and the vesting carve-out, so the curve still prices
+    const quoteMint = global.mintKeypair;
+    const communalAccount = global.linearCommunalAccount;
+    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryCreator.publicKey
+    );
+    const repositoryCreatorUsdcAccount = await getAssociatedTokenAddress(
+      quoteMint,
+      repositoryCreator.publicKey
+    );
+    const tradeAccounts = {
+      programConfig: global.programConfig,
+      communalDeposit: communalAccount,
+      communalTokenAccount: await getAssociatedTokenAddress(
+        mintKeypair,
+        communalAccount,
+        true
+      ),
+      rewardsMint: mintKeypair,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      usdcTokenProgram: TOKEN_PROGRAM_ID,
+      repositoryAccount,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: web3.SystemProgram.programId,
+      communalUsdcAccount: await getAssociatedTokenAddress(
+        quoteMint,
+        communalAccount,
+        true
+      ),
+      usdcMint: quoteMint,
+      repositoryTreasury: global.linearRepositoryTreasury,
+      repositoryTreasuryUsdcAccount: await getAssociatedTokenAddress(
+        quoteMint,
+        global.linearRepositoryTreasury,
+        true
+      ),
+      protocolTreasury: global.protocolTreasury,
+      protocolTreasuryUsdcAccount: await getAssociatedTokenAddress(
+        quoteMint,
+        global.protocolTreasury,
+        true
+      ),
+    };
+    const quoteAccounts = {
+      repositoryAccount,
+      rewardsMint: mintKeypair,
+      programConfig: global.programConfig,
+      usdcMint: quoteMint,
+    };
+
+    const buyQuote = await program.methods
+      .quoteBuy(new anchor.BN(10))
+      .accounts(quoteAccounts)
+      .view();
+    await program.methods
+      .buyTokensWithSlippage(new anchor.BN(10), buyQuote.totalUsdc)
+      .accounts({
+        ...tradeAccounts,
+        buyer: repositoryCreator.publicKey,
+        buyerTokenAccount: repositoryCreatorTokenAccount,
+        buyerUsdcAccount: repositoryCreatorUsdcAccount,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const sellQuote = await program.methods
+      .quoteSell(new anchor.BN(10))
+      .accounts(quoteAccounts)
+      .view();
+    await program.methods
+      .sellTokensWithSlippage(new anchor.BN(10), sellQuote.totalUsdc)
+      .accounts({
+        ...tradeAccounts,
+        seller: repositoryCreator.publicKey,
+        sellerTokenAccount: repositoryCreatorTokenAccount,
+        sellerUsdcAccount: repositoryCreatorUsdcAccount,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+  });
+
+  it("Quotes buy and sell prices from the live supply", async () => {
+    let [mintKeypair, repositoryAccount] = [
+      global.mintKeypair,
+      global.repositoryAccount,
+    ];
+
+    const buyQuote = await program.methods
+      .quoteBuy(new anchor.BN(10))
+      .accounts({
+        repositoryAccount,
+        rewardsMint: mintKeypair,
+        programConfig: global.programConfig,
+        usdcMint: mintKeypair,
+      })
+      .view();
+    assert.ok(
+      buyQuote.totalUsdc.eq(
+        buyQuote.usdcAmount
+          .add(buyQuote.transferFee)
+          .add(buyQuote.protocolFee)
+          .add(buyQuote.repositoryFee)
+      )
+    );
+
+    const sellQuote = await program.methods
+      .quoteSell(new anchor.BN(10))
+      .accounts({
+        repositoryAccount,
+        rewardsMint: mintKeypair,
+        programConfig: global.programConfig,
+        usdcMint: mintKeypair,
+      })
+      .view();
+    assert.ok(
+      sellQuote.totalUsdc.eq(
+        sellQuote.usdcAmount
+          .sub(sellQuote.protocolFee)
+          .sub(sellQuote.repositoryFee)
+          .sub(sellQuote.transferFee)
+      )
+    );
+  });
+
+  it("Prices buys and sells on a quadratic curve", async () => {
+    let [
+      nameRouterAccount,
+      routerCreatorKeypair,
+      repositoryCreator,
+      repositoryCreatorVerifiedAccount,
+      mintKeypair,
+    ] = [
+      global.nameRouterAccount,
+      global.routerCreatorKeypair,
+      global.repositoryCreator,
+      global.repositoryCreatorVerifiedAccount,
+      global.mintKeypair,
+    ];
+    const quadraticId = constant.repositoryId + "6";
+    const [
+      repositoryAccount,
+      repositoryCreatorTokenAccount,
+      vestingTokenAccount,
+      quadraticMint,
+      vestingAccount,
+    ] = await create_spl_token(repositoryCreator, quadraticId);
+
+    //price = 5 + 3 * supply^2, so the reserve for s tokens is 5s + s^3
+    await program.methods
+      .createRepository(
+        quadraticId,
+        constant.repositoryTitle,
+        constant.repositoryUri,
+        constant.tokenName,
+        constant.tokenimage,
+        constant.tokenMetadata,
+        {
+          curveType: { quadratic: {} },
+          slope: new anchor.BN(3),
+          basePrice: new anchor.BN(5),
+        },
+        null,
+        constant.vestingPlan
+      )
+      .accounts({
+        nameRouterAccount,
+        repositoryAccount,
+        repositoryCreatorTokenAccount,
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryVerifiedUser: repositoryCreatorVerifiedAccount,
+        rewardsMint: quadraticMint,
+        routerCreator: routerCreatorKeypair.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+        vestingAccount,
+        vestingTokenAccount,
+        metadata: await get_metadata_account(quadraticMint),
+        tokenMetadataProgram: constant.TOKEN_METADATA_PROGRAM_ID,
+        importedMint: null,
+        programConfig: global.programConfig,
+        rent: web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const [communalAccount] = await get_pda_from_seeds([
+      Buffer.from("are_we_conscious"),
+      Buffer.from("is love life ?  "),
+      Buffer.from("arewemadorinlove"),
+      quadraticMint.toBuffer(),
+    ]);
+    const communalTokenAccount = await getAssociatedTokenAddress(
+      quadraticMint,
+      communalAccount,
+      true
+    );
+    const communalUsdcAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      communalAccount,
+      true
+    );
+    const [repositoryTreasury] = await get_pda_from_seeds([
+      Buffer.from("treasury"),
+      repositoryAccount.toBuffer(),
+    ]);
+    const treasuryAccounts = {
+      repositoryTreasury,
+      repositoryTreasuryUsdcAccount: await getAssociatedTokenAddress(
+        mintKeypair,
+        repositoryTreasury,
+        true
+      ),
+      protocolTreasury: global.protocolTreasury,
+      protocolTreasuryUsdcAccount: await getAssociatedTokenAddress(
+        mintKeypair,
+        global.protocolTreasury,
+        true
+      ),
+    };
+
+    await program.methods
+      .createCommunalAccount()
+      .accounts({
+        programConfig: global.programConfig,
+        authority: repositoryCreator.publicKey,
+        communalDeposit: communalAccount,
+        communalTokenAccount,
+        systemProgram: web3.SystemProgram.programId,
+        rewardsMint: quadraticMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        usdcTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        usdcMint: mintKeypair,
+        communalUsdcAccount,
+        repositoryAccount,
+        ...treasuryAccounts,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const quoteAccounts = {
+      repositoryAccount,
+      rewardsMint: quadraticMint,
+      programConfig: global.programConfig,
+      usdcMint: mintKeypair,
+    };
+    const tradeAccounts = {
+      programConfig: global.programConfig,
+      communalDeposit: communalAccount,
+      communalTokenAccount,
+      rewardsMint: quadraticMint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      usdcTokenProgram: TOKEN_PROGRAM_ID,
+      repositoryAccount,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: web3.SystemProgram.programId,
+      communalUsdcAccount,
+      usdcMint: mintKeypair,
+      ...treasuryAccounts,
+    };
+    const repositoryCreatorUsdcAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryCreator.publicKey
+    );
+
+    //two whole tokens off an empty curve cost 5 * 2 + 2^3
+    const buyQuote = await program.methods
+      .quoteBuy(new anchor.BN(20))
+      .accounts(quoteAccounts)
+      .view();
+    assert.equal(buyQuote.usdcAmount.toNumber(), 18);
+    assert.equal(buyQuote.totalUsdc.toNumber(), 18);
+
+    await program.methods
+      .buyTokens(new anchor.BN(18), new anchor.BN(20))
+      .accounts({
+        ...tradeAccounts,
+        buyer: repositoryCreator.publicKey,
+        buyerTokenAccount: repositoryCreatorTokenAccount,
+        buyerUsdcAccount: repositoryCreatorUsdcAccount,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    //selling one of them back releases (5 * 2 + 2^3) - (5 * 1 + 1^3)
+    const sellQuote = await program.methods
+      .quoteSell(new anchor.BN(10))
+      .accounts(quoteAccounts)
+      .view();
+    assert.equal(sellQuote.usdcAmount.toNumber(), 12);
+    assert.equal(sellQuote.totalUsdc.toNumber(), 12);
+
+    await program.methods
+      .sellTokens(new anchor.BN(12), new anchor.BN(10))
+      .accounts({
+        ...tradeAccounts,
+        seller: repositoryCreator.publicKey,
+        sellerTokenAccount: repositoryCreatorTokenAccount,
+        sellerUsdcAccount: repositoryCreatorUsdcAccount,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    //what is left backs exactly the one token still in circulation
+    const reserve = await connection.getTokenAccountBalance(
+      communalUsdcAccount
+    );
+    assert.equal(reserve.value.amount, "6");
+  });
+
+  it("Rejects a repository with a degenerate curve", async () => {
+    let [
+      nameRouterAccount,
+      routerCreatorKeypair,
+      repositoryCreator,
+      repositoryCreatorVerifiedAccount,
+    ] = [
+      global.nameRouterAccount,
+      global.routerCreatorKeypair,
+      global.repositoryCreator,
+      global.repositoryCreatorVerifiedAccount,
+    ];
+    const degenerateId = constant.repositoryId + "7";
+    const [
+      repositoryAccount,
+      repositoryCreatorTokenAccount,
+      vestingTokenAccount,
+      degenerateMint,
+      vestingAccount,
+    ] = await create_spl_token(repositoryCreator, degenerateId);
+
+    //a zeroed curve would hand out every token for free
+    try {
+      await program.methods
+        .createRepository(
+          degenerateId,
+          constant.repositoryTitle,
+          constant.repositoryUri,
+          constant.tokenName,
+          constant.tokenimage,
+          constant.tokenMetadata,
+          {
+            curveType: { linear: {} },
+            slope: new anchor.BN(0),
+            basePrice: new anchor.BN(0),
+          },
+          null,
+          constant.vestingPlan
+        )
+        .accounts({
+          nameRouterAccount,
+          repositoryAccount,
+          repositoryCreatorTokenAccount,
+          repositoryCreator: repositoryCreator.publicKey,
+          repositoryVerifiedUser: repositoryCreatorVerifiedAccount,
+          rewardsMint: degenerateMint,
+          routerCreator: routerCreatorKeypair.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+          vestingAccount,
+          vestingTokenAccount,
+          metadata: await get_metadata_account(degenerateMint),
+          tokenMetadataProgram: constant.TOKEN_METADATA_PROGRAM_ID,
+          importedMint: null,
+          programConfig: global.programConfig,
+          rent: web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+      assert.fail("expected the degenerate curve to be rejected");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "InvalidCurveConfig");
+    }
+  });
+
+  it("Audits the communal reserve against the curve", async () => {
+    let [mintKeypair, communalAccount, repositoryAccount] = [
+      global.mintKeypair,
+      global.communalAccount,
+      global.repositoryAccount,
+    ];
+
+    const communalUsdcAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      communalAccount,
+      true
+    );
+
+    await program.methods
+      .auditReserve()
+      .accounts({
+        repositoryAccount,
+        rewardsMint: mintKeypair,
+        communalDeposit: communalAccount,
+        communalUsdcAccount,
+        programConfig: global.programConfig,
+      })
+      .rpc(rpcConfig);
+  });
+
+  it("Withdraws accrued trading fees from the repository treasury", async () => {
+    let [repositoryCreator, mintKeypair, repositoryAccount, repositoryTreasury] =
+      [
+        global.repositoryCreator,
+        global.mintKeypair,
+        global.repositoryAccount,
+        global.repositoryTreasury,
+      ];
+
+    const repositoryTreasuryUsdcAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryTreasury,
+      true
+    );
+
+    const repositoryCreatorUsdcAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryCreator.publicKey
+    );
+
+    await program.methods
+      .withdrawTreasuryFees(new anchor.BN(0))
+      .accounts({
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryAccount,
+        repositoryTreasury,
+        programConfig: global.programConfig,
+        repositoryTreasuryUsdcAccount,
+        repositoryCreatorUsdcAccount,
+        usdcMint: mintKeypair,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+  });
+
+  it("Grant money to objective", async () => {
+    let [mintKeypair, repositoryAccount, objectiveAccount, repositoryCreator] =
+      [
+        global.mintKeypair,
+        global.repositoryAccount,
+        global.rootObjectiveAccount,
+        global.repositoryCreator,
+      ];
+
+    const grantee = await create_keypair();
+    const [verifiedUserAccount] = await create_verified_user(
+      global.routerCreatorKeypair,
+      global.nameRouterAccount,
+      grantee.publicKey
+    );
+
+    const [granteeAccount] = await get_pda_from_seeds([
+      grantee.publicKey.toBuffer(),
+      repositoryAccount.toBuffer(),
+      objectiveAccount.toBuffer(),
+    ]);
+
+    const objectiveStakeAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      objectiveAccount,
+      true
+    );
+
+    const granteeStakeAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      grantee,
+      mintKeypair,
+      grantee.publicKey,
+      false
+    );
+
+    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryCreator.publicKey
+    );
+
+    await transfer(
+      connection,
+      repositoryCreator,
+      repositoryCreatorTokenAccount,
+      granteeStakeAccount.address,
+      repositoryCreator,
+      10
+    );
+
+    await program.methods
+      .grantMoney(new anchor.BN(10), constant.roadmapImageUrl)
+      .accounts({
+        programConfig: global.programConfig,
+        grantee: grantee.publicKey,
+        granteeVerifiedUser: verifiedUserAccount,
+        objective: objectiveAccount,
+        repository: repositoryAccount,
+        tokenMint: mintKeypair,
+        granteeAccount: granteeAccount,
+        objectiveStakeAccount: objectiveStakeAccount,
+        granteeStakeAccount: granteeStakeAccount.address,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([grantee])
+      .rpc(rpcConfig);
+    global.grantee = grantee;
+    global.granteeAccount = granteeAccount;
+  });
+  it("Disperse grant money", async () => {
+    let [
+      repositoryAccount,
+      objectiveAccount,
+      repositoryCreator,
+      issueAccount,
+      mintKeypair,
+    ] = [
+      global.repositoryAccount,
+      global.rootObjectiveAccount,
+      global.repositoryCreator,
+      global.issueAccount,
+      global.mintKeypair,
+    ];
+
+    const objectiveStakeAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      objectiveAccount,
+      true
+    );
+    const issueTokenPoolAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      issueAccount,
+      true
+    );
+    await program.methods
+      .disperseGrant(new anchor.BN(10))
+      .accounts({
+        repositoryCreator: repositoryCreator.publicKey,
+        objective: objectiveAccount,
+        objectiveStakeAccount: objectiveStakeAccount,
+        repository: repositoryAccount,
+        issueAccount: issueAccount,
+        issueTokenPoolAccount: issueTokenPoolAccount,
+        systemProgram: web3.SystemProgram.programId,
+        tokenMint: mintKeypair,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+  });
+  it("Create an objective proposal", async () => {
+    let objectiveAccount = global.rootObjectiveAccount;
+
+    const proposee = await create_keypair();
+    const [objectiveProposal] = await get_pda_from_seeds([
+      Buffer.from("objective_proposal"),
+      objectiveAccount.toBuffer(),
+      Buffer.from(constant.proposalId),
+    ]);
+    await program.methods
+      .createObjectiveProposal(constant.proposalId, constant.proposalUri)
+      .accounts({
+        proposee: proposee.publicKey,
+        objective: objectiveAccount,
+        systemProgram: web3.SystemProgram.programId,
+        objectiveProposal: objectiveProposal,
+      })
+      .signers([proposee])
+      .rpc(rpcConfig);
+    global.objectiveProposal = objectiveProposal;
+  });
+  it("Vote on objective proposal", async () => {
+    let [objectiveAccount, objectiveProposal, granteeAccount, grantee] = [
+      global.rootObjectiveAccount,
+      global.objectiveProposal,
+      global.granteeAccount,
+      global.grantee,
+    ];
+
+    const [objectiveProposalVote] = await get_pda_from_seeds([
+      objectiveProposal.toBuffer(),
+      objectiveAccount.toBuffer(),
+      grantee.publicKey.toBuffer(),
+    ]);
+
+    await program.methods
+      .voteObjective(true)
+      .accounts({
+        programConfig: global.programConfig,
+        voter: grantee.publicKey,
+        objective: objectiveAccount,
+        grantAccount: granteeAccount,
+        proposal: objectiveProposal,
+        objectiveProposalVote: objectiveProposalVote,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([grantee])
+      .rpc(rpcConfig);
+  });
+  it("Change repo token", async () => {
+    let [
+      repositoryCreator,
+      nameRouterAccount,
+      repositoryCreatorVerifiedAccount,
+      routerCreatorKeypair,
+    ] = [
+      global.repositoryCreator,
+      global.nameRouterAccount,
+      global.repositoryCreatorVerifiedAccount,
+      global.routerCreatorKeypair,
+    ];
+
+    const thirdId = constant.repositoryId + "3";
+    const importedMint = await createMint(
+      connection,
+      repositoryCreator,
+      repositoryCreator.publicKey,
+      repositoryCreator.publicKey,
+      3
+    );
+
+    const [repositoryAccount] = await get_pda_from_seeds([
+      Buffer.from("repository"),
+      Buffer.from(thirdId),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+
+    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
+      importedMint,
+      repositoryCreator.publicKey
+    );
+
+    //creating a new repo as cant change repo token of repo with existing issues
+    await program.methods
+      .createRepository(
+        thirdId,
+        constant.repositoryTitle,
+        constant.repositoryUri,
+        constant.tokenName,
+        constant.tokenimage,
+        constant.tokenMetadata,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        nameRouterAccount,
+        repositoryAccount,
+        repositoryCreatorTokenAccount,
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryVerifiedUser: repositoryCreatorVerifiedAccount,
+        rewardsMint: null,
+        routerCreator: routerCreatorKeypair.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+        vestingAccount: null,
+        vestingTokenAccount: null,
+        metadata: null,
+        tokenMetadataProgram: constant.TOKEN_METADATA_PROGRAM_ID,
+        importedMint: importedMint,
+        programConfig: global.programConfig,
+        rent: web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const secondMintAddress = await createMint(
+      connection,
+      repositoryCreator,
+      repositoryCreator.publicKey,
+      repositoryCreator.publicKey,
+      6
+    );
+
+    await program.methods
+      .changeRepoToken(
+        constant.tokenName,
+        constant.tokenMetadata,
+        constant.tokenimage,
+        null
+      )
+      .accounts({
+        repositoryCreator: repositoryCreator.publicKey,
+        repositoryCreatorTokenAccount: repositoryCreatorTokenAccount,
+        vestingAccount: null,
+        vestingTokenAccount: null,
+        rewardsMint: null,
+        importedMint: secondMintAddress,
+        repositoryAccount: repositoryAccount,
+        metadata: null,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: constant.TOKEN_METADATA_PROGRAM_ID,
+        rent: web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+  });
+
+  it("Vote and close issue", async () => {
+    //creating a new issue as initial one is already closed
+    let [
+      nameRouterAccount,
+      routerCreatorKeypair,
+      repositoryCreator,
+      repositoryAccount,
+      mintKeypair,
+    ] = [
+      global.nameRouterAccount,
+      global.routerCreatorKeypair,
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.mintKeypair,
+    ];
+
+    const issueCreatorKeypair = await create_keypair();
+
+    const { issueIndex } = await program.account.repository.fetch(
+      repositoryAccount
+    );
+
+    // Adding issue creator user
+
+    const [issueVerifiedUser] = await create_verified_user(
+      routerCreatorKeypair,
+      nameRouterAccount,
+      issueCreatorKeypair.publicKey
+    );
+    // Creating issue
+    const issueURI = `https://github.com/${constant.userName}/${constant.repositoryId}/issues/${issueIndex}`;
+
+    const [issueAccount] = await get_pda_from_seeds([
+      Buffer.from("issue"),
+      Buffer.from(issueIndex.toString()),
+      repositoryAccount.toBuffer(),
+      issueCreatorKeypair.publicKey.toBuffer(),
+    ]);
+
+    await program.methods
+      .addIssue(issueURI, null, [], null, null)
+      .accounts({
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        issueAccount,
+        issueCreator: issueCreatorKeypair.publicKey,
+        issueVerifiedUser,
+        nameRouterAccount,
+        repositoryAccount,
+        routerCreator: routerCreatorKeypair.publicKey,
+        repositoryCreator: repositoryCreator.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([issueCreatorKeypair])
+      .rpc(rpcConfig);
+
+    const issueTokenPoolAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      issueAccount,
+      true
+    );
+
+    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryCreator.publicKey
+    );
+
+    // Staking tokens on a issue
+    const [issueStakerAccount] = await get_pda_from_seeds([
+      Buffer.from("issuestaker"),
+      issueAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+
+    await program.methods
+      .stakeIssue(new anchor.BN(10), new anchor.BN(0))
+      .accounts({
+        issueAccount,
+        repositoryAccount,
+        issueTokenPoolAccount,
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount,
+        issueStakerTokenAccount: repositoryCreatorTokenAccount,
+        rewardsMint: mintKeypair,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        pullRequestMetadataAccount: null,
+        issueStakerVerifiedUser: null,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const pullRequestCreator = await create_keypair();
+    const [pullRequestCreatorVerifiedAccount] = await create_verified_user(
+      routerCreatorKeypair,
+      nameRouterAccount,
+      pullRequestCreator.publicKey
+    );
+
+    const [pullRequestMetadataAccount] = await get_pda_from_seeds([
+      Buffer.from("pullrequestadded"),
+      issueAccount.toBuffer(),
+      pullRequestCreator.publicKey.toBuffer(),
+    ]);
+
+    const pullRequestTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      pullRequestMetadataAccount,
+      true
+    );
+
+    await program.methods
+      .addPr(constant.pullRequestMetadataUri)
+      .accounts({
+        pullRequestVerifiedUser: pullRequestCreatorVerifiedAccount,
+        issue: issueAccount,
+        pullRequestMetadataAccount: pullRequestMetadataAccount,
+        nameRouterAccount,
+        pullRequestTokenAccount,
+        pullRequestAddr: pullRequestCreator.publicKey,
+        repositoryAccount,
+        routerCreator: routerCreatorKeypair.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rewardsMint: mintKeypair,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([pullRequestCreator])
+      .rpc(rpcConfig);
+
+    await program.methods
+      .votePr()
+      .accounts({
+        programConfig: global.programConfig,
+        issueAccount: issueAccount,
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount: issueStakerAccount,
+        pullRequestMetadataAccount: pullRequestMetadataAccount,
+        repository: repositoryAccount,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const initiator = await create_keypair();
+
+    await program.methods
+      .acceptIssueVote()
+      .accounts({
+        initiator: initiator.publicKey,
+        repositoryAccount: repositoryAccount,
+        issue: issueAccount,
+        pullRequestMetadataAccount: pullRequestMetadataAccount,
+        programConfig: global.programConfig,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([initiator])
+      .rpc(rpcConfig);
+  });
+
+  it("Delegates PR voting power to another verified user", async () => {
+    let [
+      repositoryCreator,
+      repositoryAccount,
+      mintKeypair,
+      issueCreator,
+      issueVerifiedUser,
+      pullRequestCreator,
+      pullRequestCreatorVerifiedAccount,
+    ] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.mintKeypair,
+      global.issueCreator,
+      global.issueVerifiedUser,
+      global.pullRequestCreator,
+      global.pullRequestCreatorVerifiedAccount,
+    ];
+
+    const { issueIndex } = await program.account.repository.fetch(
+      repositoryAccount
+    );
+    const [issueAccount] = await get_pda_from_seeds([
+      Buffer.from("issue"),
+      Buffer.from(issueIndex.toString()),
+      repositoryAccount.toBuffer(),
+      issueCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .addIssue(constant.repositoryUri, null, [], null, null)
+      .accounts({
+        issueAccount,
+        issueCreator: issueCreator.publicKey,
+        issueVerifiedUser,
+        repositoryAccount,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([issueCreator])
+      .rpc(rpcConfig);
+
+    const [issueStakerAccount] = await get_pda_from_seeds([
+      Buffer.from("issuestaker"),
+      issueAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .stakeIssue(new anchor.BN(10), new anchor.BN(0))
+      .accounts({
+        issueAccount,
+        repositoryAccount,
+        issueTokenPoolAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          issueAccount,
+          true
+        ),
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount,
+        issueStakerTokenAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          repositoryCreator.publicKey
+        ),
+        rewardsMint: mintKeypair,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        pullRequestMetadataAccount: null,
+        issueStakerVerifiedUser: null,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    //the issue creator reviews pull requests on behalf of the staker
+    const delegation = {
+      issueStaker: repositoryCreator.publicKey,
+      issueAccount,
+      issueStakerAccount,
+    };
+    await program.methods
+      .delegateVote()
+      .accounts({ ...delegation, delegateVerifiedUser: issueVerifiedUser })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const [pullRequestMetadataAccount] = await get_pda_from_seeds([
+      Buffer.from("pullrequestadded"),
+      issueAccount.toBuffer(),
+      pullRequestCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .addPr(constant.pullRequestMetadataUri)
+      .accounts({
+        pullRequestVerifiedUser: pullRequestCreatorVerifiedAccount,
+        issue: issueAccount,
+        pullRequestMetadataAccount,
+        pullRequestAddr: pullRequestCreator.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([pullRequestCreator])
+      .rpc(rpcConfig);
+
+    await program.methods
+      .votePr()
+      .accounts({
+        programConfig: global.programConfig,
+        issueAccount,
+        issueStaker: issueCreator.publicKey,
+        issueStakerAccount: null,
+        pullRequestMetadataAccount,
+        repository: repositoryAccount,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: issueStakerAccount, isWritable: true, isSigner: false },
+      ])
+      .signers([issueCreator])
+      .rpc(rpcConfig);
+
+    const pullRequest = await program.account.pullRequest.fetch(
+      pullRequestMetadataAccount
+    );
+    assert.equal(pullRequest.totalVotedAmount.toNumber(), 10);
+    assert.equal(pullRequest.voterCount.toNumber(), 1);
+
+    //the delegated vote has been cast so it can no longer be taken back
+    try {
+      await program.methods
+        .revokeVoteDelegation()
+        .accounts(delegation)
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+      assert.fail("revoked a delegation that was already used");
+    } catch (err) {
+      assert.equal(
+        err.error.errorCode.code,
+        "PullRequestVotingClosedAlready"
+      );
+    }
+  });
+
+  it("Changes and withdraws a PR vote while voting is open", async () => {
+    let [
+      repositoryCreator,
+      repositoryAccount,
+      mintKeypair,
+      issueCreator,
+      issueVerifiedUser,
+      pullRequestCreator,
+      pullRequestCreatorVerifiedAccount,
+    ] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.mintKeypair,
+      global.issueCreator,
+      global.issueVerifiedUser,
+      global.pullRequestCreator,
+      global.pullRequestCreatorVerifiedAccount,
+    ];
+
+    const { issueIndex } = await program.account.repository.fetch(
+      repositoryAccount
+    );
+    const [issueAccount] = await get_pda_from_seeds([
+      Buffer.from("issue"),
+      Buffer.from(issueIndex.toString()),
+      repositoryAccount.toBuffer(),
+      issueCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .addIssue(constant.repositoryUri, null, [], null, null)
+      .accounts({
+        issueAccount,
+        issueCreator: issueCreator.publicKey,
+        issueVerifiedUser,
+        repositoryAccount,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([issueCreator])
+      .rpc(rpcConfig);
+
+    const issueTokenPoolAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      issueAccount,
+      true
+    );
+    const [issueStakerAccount] = await get_pda_from_seeds([
+      Buffer.from("issuestaker"),
+      issueAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .stakeIssue(new anchor.BN(10), new anchor.BN(0))
+      .accounts({
+        issueAccount,
+        repositoryAccount,
+        issueTokenPoolAccount,
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount,
+        issueStakerTokenAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          repositoryCreator.publicKey
+        ),
+        rewardsMint: mintKeypair,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        pullRequestMetadataAccount: null,
+        issueStakerVerifiedUser: null,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    //two competing pull requests for the same issue
+    const pullRequests = [];
+    for (const [author, verifiedUser] of [
+      [pullRequestCreator, pullRequestCreatorVerifiedAccount],
+      [issueCreator, issueVerifiedUser],
+    ]) {
+      const [pullRequestMetadataAccount] = await get_pda_from_seeds([
+        Buffer.from("pullrequestadded"),
+        issueAccount.toBuffer(),
+        author.publicKey.toBuffer(),
+      ]);
+      await program.methods
+        .addPr(constant.pullRequestMetadataUri)
+        .accounts({
+          pullRequestVerifiedUser: verifiedUser,
+          issue: issueAccount,
+          pullRequestMetadataAccount,
+          pullRequestAddr: author.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([author])
+        .rpc(rpcConfig);
+      pullRequests.push(pullRequestMetadataAccount);
+    }
+    const [firstPullRequest, secondPullRequest] = pullRequests;
+
+    const voteAccounts = {
+      programConfig: global.programConfig,
+      issueAccount,
+      issueStaker: repositoryCreator.publicKey,
+      issueStakerAccount,
+      repository: repositoryAccount,
+      systemProgram: web3.SystemProgram.programId,
+    };
+    await program.methods
+      .votePr()
+      .accounts({
+        ...voteAccounts,
+        pullRequestMetadataAccount: firstPullRequest,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    //voting a second time must go through change_vote
+    try {
+      await program.methods
+        .votePr()
+        .accounts({
+          ...voteAccounts,
+          pullRequestMetadataAccount: secondPullRequest,
+        })
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+      assert.fail("voted twice with the same stake");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "AlreadyVoted");
+    }
+
+    await program.methods
+      .changeVote()
+      .accounts({
+        voter: repositoryCreator.publicKey,
+        issueAccount,
+        issueStakerAccount,
+        votedPullRequest: firstPullRequest,
+        pullRequestMetadataAccount: secondPullRequest,
+        programConfig: global.programConfig,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    let first = await program.account.pullRequest.fetch(firstPullRequest);
+    let second = await program.account.pullRequest.fetch(secondPullRequest);
+    assert.equal(first.totalVotedAmount.toNumber(), 0);
+    assert.equal(second.totalVotedAmount.toNumber(), 10);
+    assert.equal(first.voterCount.toNumber(), 0);
+    assert.equal(second.voterCount.toNumber(), 1);
+
+    await program.methods
+      .withdrawVote()
+      .accounts({
+        voter: repositoryCreator.publicKey,
+        issueAccount,
+        issueStakerAccount,
+        pullRequestMetadataAccount: secondPullRequest,
+        programConfig: global.programConfig,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    second = await program.account.pullRequest.fetch(secondPullRequest);
+    const issueStaker = await program.account.issueStaker.fetch(
+      issueStakerAccount
+    );
+    assert.equal(second.totalVotedAmount.toNumber(), 0);
+    assert.equal(second.voterCount.toNumber(), 0);
+    assert.equal(issueStaker.prVotingPower.toNumber(), 10);
+    assert.isFalse(issueStaker.hasVoted);
+    assert.isNull(issueStaker.votedOn);
+
+    //with the vote withdrawn the stake can leave the issue again
+    await program.methods
+      .unstakeIssue(new anchor.BN(10))
+      .accounts({
+        issueAccount,
+        repositoryAccount,
+        issueTokenPoolAccount,
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount,
+        issueStakerTokenAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          repositoryCreator.publicKey
+        ),
+        rewardsMint: mintKeypair,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    assert.isNull(await connection.getAccountInfo(issueStakerAccount));
+  });
+
+  it("Tallies PR votes quadratically when the repository opts in", async () => {
+    let [
+      repositoryCreator,
+      repositoryAccount,
+      mintKeypair,
+      issueCreator,
+      issueVerifiedUser,
+      pullRequestCreator,
+      pullRequestCreatorVerifiedAccount,
+    ] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.mintKeypair,
+      global.issueCreator,
+      global.issueVerifiedUser,
+      global.pullRequestCreator,
+      global.pullRequestCreatorVerifiedAccount,
+    ];
+
+    const setVotingMode = (votingMode) =>
+      program.methods
+        .setVotingMode(votingMode)
+        .accounts({
+          repositoryCreator: repositoryCreator.publicKey,
+          repositoryAccount,
+        })
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+
+    await setVotingMode({ quadratic: {} });
+
+    const { issueIndex } = await program.account.repository.fetch(
+      repositoryAccount
+    );
+    const [issueAccount] = await get_pda_from_seeds([
+      Buffer.from("issue"),
+      Buffer.from(issueIndex.toString()),
+      repositoryAccount.toBuffer(),
+      issueCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .addIssue(constant.repositoryUri, null, [], null, null)
+      .accounts({
+        issueAccount,
+        issueCreator: issueCreator.publicKey,
+        issueVerifiedUser,
+        repositoryAccount,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([issueCreator])
+      .rpc(rpcConfig);
+
+    //issues keep the mode they were created with
+    await setVotingMode({ linear: {} });
+
+    const [issueStakerAccount] = await get_pda_from_seeds([
+      Buffer.from("issuestaker"),
+      issueAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+    const issueTokenPoolAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      issueAccount,
+      true
+    );
+    const issueStakerTokenAccount = await getAssociatedTokenAddress(
+      mintKeypair,
+      repositoryCreator.publicKey
+    );
+    const quadraticStake = (issueStakerVerifiedUser) =>
+      program.methods
+        .stakeIssue(new anchor.BN(16), new anchor.BN(0))
+        .accounts({
+          issueAccount,
+          repositoryAccount,
+          issueTokenPoolAccount,
+          issueStaker: repositoryCreator.publicKey,
+          issueStakerAccount,
+          issueStakerTokenAccount,
+          rewardsMint: mintKeypair,
+          systemProgram: web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          pullRequestMetadataAccount: null,
+          issueStakerVerifiedUser,
+        })
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+
+    //a quadratic tally only holds up if every staker is a distinct verified user
+    try {
+      await quadraticStake(null);
+      assert.fail("staked on a quadratic issue without being verified");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "VerifiedUserRequired");
+    }
+    await quadraticStake(global.repositoryCreatorVerifiedAccount);
+
+    const issue = await program.account.issue.fetch(issueAccount);
+    assert.deepEqual(issue.votingMode, { quadratic: {} });
+    assert.equal(issue.totalVotingPower.toNumber(), 4);
+
+    const [pullRequestMetadataAccount] = await get_pda_from_seeds([
+      Buffer.from("pullrequestadded"),
+      issueAccount.toBuffer(),
+      pullRequestCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .addPr(constant.pullRequestMetadataUri)
+      .accounts({
+        pullRequestVerifiedUser: pullRequestCreatorVerifiedAccount,
+        issue: issueAccount,
+        pullRequestMetadataAccount,
+        pullRequestAddr: pullRequestCreator.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([pullRequestCreator])
+      .rpc(rpcConfig);
+
+    await program.methods
+      .votePr()
+      .accounts({
+        programConfig: global.programConfig,
+        issueAccount,
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount,
+        pullRequestMetadataAccount,
+        repository: repositoryAccount,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+
+    const pullRequest = await program.account.pullRequest.fetch(
+      pullRequestMetadataAccount
+    );
+    assert.equal(pullRequest.totalVotedAmount.toNumber(), 4);
+  });
+
+  it("Applies the repository quorum and early finalisation rules", async () => {
+    let [
+      repositoryCreator,
+      repositoryAccount,
+      mintKeypair,
+      issueCreator,
+      issueVerifiedUser,
+      pullRequestCreator,
+      pullRequestCreatorVerifiedAccount,
+    ] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.mintKeypair,
+      global.issueCreator,
+      global.issueVerifiedUser,
+      global.pullRequestCreator,
+      global.pullRequestCreatorVerifiedAccount,
+    ];
+
+    const setGovernanceConfig = (governanceConfig) =>
+      program.methods
+        .setGovernanceConfig(governanceConfig)
+        .accounts({
+          repositoryCreator: repositoryCreator.publicKey,
+          repositoryAccount,
+        })
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+    const setVotingEnd = (votingEnd) =>
+      program.methods
+        .updateProgramConfig(null, null, new anchor.BN(votingEnd), null)
+        .accounts({
+          admin: repositoryCreator.publicKey,
+          programConfig: global.programConfig,
+        })
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+
+    try {
+      await setGovernanceConfig({
+        quorumBps: 5000,
+        approvalThresholdBps: 10000,
+        allowEarlyFinalisation: true,
+      });
+      assert.fail("accepted an unreachable approval threshold");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "InvalidGovernanceConfig");
+    }
+
+    //every staker has to vote and the vote has to run its full course
+    await setGovernanceConfig({
+      quorumBps: 10000,
+      approvalThresholdBps: 5000,
+      allowEarlyFinalisation: false,
+    });
+
+    const { issueIndex } = await program.account.repository.fetch(
+      repositoryAccount
+    );
+    const [issueAccount] = await get_pda_from_seeds([
+      Buffer.from("issue"),
+      Buffer.from(issueIndex.toString()),
+      repositoryAccount.toBuffer(),
+      issueCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .addIssue(constant.repositoryUri, null, [], null, null)
+      .accounts({
+        issueAccount,
+        issueCreator: issueCreator.publicKey,
+        issueVerifiedUser,
+        repositoryAccount,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([issueCreator])
+      .rpc(rpcConfig);
+
+    const [issueStakerAccount] = await get_pda_from_seeds([
+      Buffer.from("issuestaker"),
+      issueAccount.toBuffer(),
+      repositoryCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .stakeIssue(new anchor.BN(10), new anchor.BN(0))
       .accounts({
-        nameRouterAccount,
+        issueAccount,
         repositoryAccount,
-        repositoryCreatorTokenAccount,
-        repositoryCreator: repositoryCreator.publicKey,
-        repositoryVerifiedUser: repositoryCreatorVerifiedAccount,
-        rewardsMint: null,
-        routerCreator: routerCreatorKeypair.publicKey,
+        issueTokenPoolAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          issueAccount,
+          true
+        ),
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount,
+        issueStakerTokenAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          repositoryCreator.publicKey
+        ),
+        rewardsMint: mintKeypair,
         systemProgram: web3.SystemProgram.programId,
-        vestingAccount: null,
-        vestingTokenAccount: null,
-        metadata: null,
-        tokenMetadataProgram: constant.TOKEN_METADATA_PROGRAM_ID,
-        importedMint: importedMint,
-        rent: web3.SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        pullRequestMetadataAccount: null,
+        issueStakerVerifiedUser: null,
       })
       .signers([repositoryCreator])
       .rpc(rpcConfig);
 
-    const secondMintAddress = await createMint(
-      connection,
-      repositoryCreator,
-      repositoryCreator.publicKey,
-      repositoryCreator.publicKey,
-      6
-    );
+    const [pullRequestMetadataAccount] = await get_pda_from_seeds([
+      Buffer.from("pullrequestadded"),
+      issueAccount.toBuffer(),
+      pullRequestCreator.publicKey.toBuffer(),
+    ]);
+    await program.methods
+      .addPr(constant.pullRequestMetadataUri)
+      .accounts({
+        pullRequestVerifiedUser: pullRequestCreatorVerifiedAccount,
+        issue: issueAccount,
+        pullRequestMetadataAccount,
+        pullRequestAddr: pullRequestCreator.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([pullRequestCreator])
+      .rpc(rpcConfig);
+
+    const acceptIssueVote = () =>
+      program.methods
+        .acceptIssueVote()
+        .accounts({
+          initiator: repositoryCreator.publicKey,
+          repositoryAccount,
+          issue: issueAccount,
+          pullRequestMetadataAccount,
+          programConfig: global.programConfig,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
 
+    try {
+      await acceptIssueVote();
+      assert.fail("finalised before the voting period ended");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "VotingPeriodOnGoing");
+    }
+
+    //finalising early still has to reach the quorum
+    await setGovernanceConfig({
+      quorumBps: 10000,
+      approvalThresholdBps: 5000,
+      allowEarlyFinalisation: true,
+    });
+    try {
+      await acceptIssueVote();
+      assert.fail("finalised early without reaching quorum");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "QuorumNotReached");
+    }
+    await setGovernanceConfig({
+      quorumBps: 10000,
+      approvalThresholdBps: 5000,
+      allowEarlyFinalisation: false,
+    });
+
+    //close the voting window without anyone having voted
+    await setVotingEnd(1);
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    try {
+      await acceptIssueVote();
+      assert.fail("merged without reaching quorum");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "QuorumNotReached");
+    }
+
+    //reopen the window, vote with the whole stake and let it close again
+    await setVotingEnd(72 * 60 * 60);
     await program.methods
-      .changeRepoToken(
-        constant.tokenName,
-        constant.tokenMetadata,
-        constant.tokenimage
-      )
+      .votePr()
       .accounts({
-        repositoryCreator: repositoryCreator.publicKey,
-        repositoryCreatorTokenAccount: repositoryCreatorTokenAccount,
-        vestingAccount: null,
-        vestingTokenAccount: null,
-        rewardsMint: null,
-        importedMint: secondMintAddress,
-        repositoryAccount: repositoryAccount,
-        metadata: null,
+        programConfig: global.programConfig,
+        issueAccount,
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount,
+        pullRequestMetadataAccount,
+        repository: repositoryAccount,
         systemProgram: web3.SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        tokenMetadataProgram: constant.TOKEN_METADATA_PROGRAM_ID,
-        rent: web3.SYSVAR_RENT_PUBKEY,
       })
       .signers([repositoryCreator])
       .rpc(rpcConfig);
+    await setVotingEnd(1);
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await acceptIssueVote();
+
+    const issue = await program.account.issue.fetch(issueAccount);
+    assert.isNotNull(issue.closedAt);
+    assert.equal(issue.totalVotesCast.toNumber(), 10);
+
+    await setVotingEnd(72 * 60 * 60);
+    await setGovernanceConfig({
+      quorumBps: 0,
+      approvalThresholdBps: 5000,
+      allowEarlyFinalisation: true,
+    });
   });
 
-  it("Vote and close issue", async () => {
-    //creating a new issue as initial one is already closed
+  it("Updates, withdraws and closes pull requests", async () => {
     let [
-      nameRouterAccount,
-      routerCreatorKeypair,
       repositoryCreator,
       repositoryAccount,
       mintKeypair,
+      issueCreator,
+      issueVerifiedUser,
+      pullRequestCreator,
+      pullRequestCreatorVerifiedAccount,
     ] = [
-      global.nameRouterAccount,
-      global.routerCreatorKeypair,
       global.repositoryCreator,
       global.repositoryAccount,
       global.mintKeypair,
+      global.issueCreator,
+      global.issueVerifiedUser,
+      global.pullRequestCreator,
+      global.pullRequestCreatorVerifiedAccount,
     ];
 
-    const issueCreatorKeypair = await create_keypair();
-
     const { issueIndex } = await program.account.repository.fetch(
       repositoryAccount
     );
-
-    // Adding issue creator user
-
-    const [issueVerifiedUser] = await create_verified_user(
-      routerCreatorKeypair,
-      nameRouterAccount,
-      issueCreatorKeypair.publicKey
-    );
-    // Creating issue
-    const issueURI = `https://github.com/${constant.userName}/${constant.repositoryId}/issues/${issueIndex}`;
-
     const [issueAccount] = await get_pda_from_seeds([
       Buffer.from("issue"),
       Buffer.from(issueIndex.toString()),
       repositoryAccount.toBuffer(),
-      issueCreatorKeypair.publicKey.toBuffer(),
+      issueCreator.publicKey.toBuffer(),
     ]);
-
     await program.methods
-      .addIssue(issueURI)
+      .addIssue(constant.repositoryUri, null, [], null, null)
       .accounts({
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         issueAccount,
-        issueCreator: issueCreatorKeypair.publicKey,
+        issueCreator: issueCreator.publicKey,
         issueVerifiedUser,
-        nameRouterAccount,
         repositoryAccount,
-        routerCreator: routerCreatorKeypair.publicKey,
-        repositoryCreator: repositoryCreator.publicKey,
         systemProgram: web3.SystemProgram.programId,
       })
-      .signers([issueCreatorKeypair])
+      .signers([issueCreator])
       .rpc(rpcConfig);
 
-    const issueTokenPoolAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      issueAccount,
-      true
+    const [acceptedPullRequest] = await get_pda_from_seeds([
+      Buffer.from("pullrequestadded"),
+      issueAccount.toBuffer(),
+      pullRequestCreator.publicKey.toBuffer(),
+    ]);
+    const [rejectedPullRequest] = await get_pda_from_seeds([
+      Buffer.from("pullrequestadded"),
+      issueAccount.toBuffer(),
+      issueCreator.publicKey.toBuffer(),
+    ]);
+    const addPr = (author, verifiedUser, pullRequestMetadataAccount) =>
+      program.methods
+        .addPr(constant.pullRequestMetadataUri)
+        .accounts({
+          pullRequestVerifiedUser: verifiedUser,
+          issue: issueAccount,
+          pullRequestMetadataAccount,
+          pullRequestAddr: author.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([author])
+        .rpc(rpcConfig);
+    await addPr(
+      pullRequestCreator,
+      pullRequestCreatorVerifiedAccount,
+      acceptedPullRequest
     );
+    await addPr(issueCreator, issueVerifiedUser, rejectedPullRequest);
 
-    const repositoryCreatorTokenAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      repositoryCreator.publicKey
+    const updatedUri = constant.pullRequestMetadataUri + "/v2";
+    await program.methods
+      .updatePr(updatedUri)
+      .accounts({
+        pullRequestAddr: issueCreator.publicKey,
+        issue: issueAccount,
+        pullRequestMetadataAccount: rejectedPullRequest,
+      })
+      .signers([issueCreator])
+      .rpc(rpcConfig);
+    const rejected = await program.account.pullRequest.fetch(
+      rejectedPullRequest
     );
+    assert.equal(rejected.metadataUri, updatedUri);
+
+    //a pull request nobody voted on can be taken back and sent again
+    await program.methods
+      .withdrawPr()
+      .accounts({
+        pullRequestAddr: issueCreator.publicKey,
+        issue: issueAccount,
+        pullRequestMetadataAccount: rejectedPullRequest,
+      })
+      .signers([issueCreator])
+      .rpc(rpcConfig);
+    assert.isNull(await connection.getAccountInfo(rejectedPullRequest));
+    await addPr(issueCreator, issueVerifiedUser, rejectedPullRequest);
 
-    // Staking tokens on a issue
     const [issueStakerAccount] = await get_pda_from_seeds([
       Buffer.from("issuestaker"),
       issueAccount.toBuffer(),
       repositoryCreator.publicKey.toBuffer(),
     ]);
-
     await program.methods
-      .stakeIssue(new anchor.BN(10))
+      .stakeIssue(new anchor.BN(10), new anchor.BN(0))
       .accounts({
         issueAccount,
         repositoryAccount,
-        issueTokenPoolAccount,
+        issueTokenPoolAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          issueAccount,
+          true
+        ),
         issueStaker: repositoryCreator.publicKey,
         issueStakerAccount,
-        issueStakerTokenAccount: repositoryCreatorTokenAccount,
+        issueStakerTokenAccount: await getAssociatedTokenAddress(
+          mintKeypair,
+          repositoryCreator.publicKey
+        ),
         rewardsMint: mintKeypair,
         systemProgram: web3.SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         pullRequestMetadataAccount: null,
+        issueStakerVerifiedUser: null,
+      })
+      .signers([repositoryCreator])
+      .rpc(rpcConfig);
+    await program.methods
+      .votePr()
+      .accounts({
+        programConfig: global.programConfig,
+        issueAccount,
+        issueStaker: repositoryCreator.publicKey,
+        issueStakerAccount,
+        pullRequestMetadataAccount: acceptedPullRequest,
+        repository: repositoryAccount,
+        systemProgram: web3.SystemProgram.programId,
       })
       .signers([repositoryCreator])
       .rpc(rpcConfig);
 
-    const pullRequestCreator = await create_keypair();
-    const [pullRequestCreatorVerifiedAccount] = await create_verified_user(
-      routerCreatorKeypair,
-      nameRouterAccount,
-      pullRequestCreator.publicKey
-    );
+    try {
+      await program.methods
+        .withdrawPr()
+        .accounts({
+          pullRequestAddr: pullRequestCreator.publicKey,
+          issue: issueAccount,
+          pullRequestMetadataAccount: acceptedPullRequest,
+        })
+        .signers([pullRequestCreator])
+        .rpc(rpcConfig);
+      assert.fail("withdrew a pull request that has votes");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "PullRequestHasVotes");
+    }
 
-    const [pullRequestMetadataAccount] = await get_pda_from_seeds([
-      Buffer.from("pullrequestadded"),
-      issueAccount.toBuffer(),
-      pullRequestCreator.publicKey.toBuffer(),
-    ]);
+    const closePr = (author, pullRequestMetadataAccount) =>
+      program.methods
+        .closePr()
+        .accounts({
+          initiator: repositoryCreator.publicKey,
+          pullRequestAddr: author.publicKey,
+          issue: issueAccount,
+          pullRequestMetadataAccount,
+        })
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
 
-    const pullRequestTokenAccount = await getAssociatedTokenAddress(
-      mintKeypair,
-      pullRequestMetadataAccount,
-      true
-    );
+    try {
+      await closePr(issueCreator, rejectedPullRequest);
+      assert.fail("closed a pull request while the issue is open");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "IssueNotClosed");
+    }
 
     await program.methods
-      .addPr(constant.pullRequestMetadataUri)
+      .acceptIssueVote()
       .accounts({
-        pullRequestVerifiedUser: pullRequestCreatorVerifiedAccount,
-        issue: issueAccount,
-        pullRequestMetadataAccount: pullRequestMetadataAccount,
-        nameRouterAccount,
-        pullRequestTokenAccount,
-        pullRequestAddr: pullRequestCreator.publicKey,
+        initiator: repositoryCreator.publicKey,
         repositoryAccount,
-        routerCreator: routerCreatorKeypair.publicKey,
+        issue: issueAccount,
+        pullRequestMetadataAccount: acceptedPullRequest,
+        programConfig: global.programConfig,
         systemProgram: web3.SystemProgram.programId,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        rewardsMint: mintKeypair,
-        tokenProgram: TOKEN_PROGRAM_ID,
       })
-      .signers([pullRequestCreator])
+      .signers([repositoryCreator])
       .rpc(rpcConfig);
 
+    //rent of the rejected pull request goes back to its author
+    const authorBalance = await connection.getBalance(issueCreator.publicKey);
+    await closePr(issueCreator, rejectedPullRequest);
+    assert.isNull(await connection.getAccountInfo(rejectedPullRequest));
+    assert.isAbove(
+      await connection.getBalance(issueCreator.publicKey),
+      authorBalance
+    );
+
+    try {
+      await closePr(pullRequestCreator, acceptedPullRequest);
+      assert.fail("closed the accepted pull request");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "PullRequestAlreadyAccepted");
+    }
+  });
+
+  it("Needs a majority of all voting power after the window under the default governance", async () => {
+    let [
+      repositoryCreator,
+      repositoryAccount,
+      mintKeypair,
+      issueCreator,
+      issueVerifiedUser,
+      pullRequestCreator,
+      pullRequestCreatorVerifiedAccount,
+    ] = [
+      global.repositoryCreator,
+      global.repositoryAccount,
+      global.mintKeypair,
+      global.issueCreator,
+      global.issueVerifiedUser,
+      global.pullRequestCreator,
+      global.pullRequestCreatorVerifiedAccount,
+    ];
+
+    const setVotingEnd = (votingEnd) =>
+      program.methods
+        .updateProgramConfig(null, null, new anchor.BN(votingEnd), null)
+        .accounts({
+          admin: repositoryCreator.publicKey,
+          programConfig: global.programConfig,
+        })
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+
+    const { issueIndex } = await program.account.repository.fetch(
+      repositoryAccount
+    );
+    const [issueAccount] = await get_pda_from_seeds([
+      Buffer.from("issue"),
+      Buffer.from(issueIndex.toString()),
+      repositoryAccount.toBuffer(),
+      issueCreator.publicKey.toBuffer(),
+    ]);
     await program.methods
-      .votePr()
+      .addIssue(constant.repositoryUri, null, [], null, null)
       .accounts({
-        issueAccount: issueAccount,
-        issueStaker: repositoryCreator.publicKey,
-        issueStakerAccount: issueStakerAccount,
-        pullRequestMetadataAccount: pullRequestMetadataAccount,
-        repository: repositoryAccount,
+        issueAccount,
+        issueCreator: issueCreator.publicKey,
+        issueVerifiedUser,
+        repositoryAccount,
         systemProgram: web3.SystemProgram.programId,
       })
-      .signers([repositoryCreator])
+      .signers([issueCreator])
       .rpc(rpcConfig);
 
-    const initiator = await create_keypair();
+    //a second staker holding the other half of the voting power
+    const secondStaker = await create_keypair();
+    const secondStakerTokenAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      secondStaker,
+      mintKeypair,
+      secondStaker.publicKey,
+      false
+    );
+    await transfer(
+      connection,
+      repositoryCreator,
+      await getAssociatedTokenAddress(mintKeypair, repositoryCreator.publicKey),
+      secondStakerTokenAccount.address,
+      repositoryCreator,
+      10
+    );
 
+    const stakerAccounts = [];
+    for (const staker of [repositoryCreator, secondStaker]) {
+      const [issueStakerAccount] = await get_pda_from_seeds([
+        Buffer.from("issuestaker"),
+        issueAccount.toBuffer(),
+        staker.publicKey.toBuffer(),
+      ]);
+      await program.methods
+        .stakeIssue(new anchor.BN(10), new anchor.BN(0))
+        .accounts({
+          issueAccount,
+          repositoryAccount,
+          issueTokenPoolAccount: await getAssociatedTokenAddress(
+            mintKeypair,
+            issueAccount,
+            true
+          ),
+          issueStaker: staker.publicKey,
+          issueStakerAccount,
+          issueStakerTokenAccount: await getAssociatedTokenAddress(
+            mintKeypair,
+            staker.publicKey
+          ),
+          rewardsMint: mintKeypair,
+          systemProgram: web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          pullRequestMetadataAccount: null,
+          issueStakerVerifiedUser: null,
+        })
+        .signers([staker])
+        .rpc(rpcConfig);
+      stakerAccounts.push(issueStakerAccount);
+    }
+
+    const [pullRequestMetadataAccount] = await get_pda_from_seeds([
+      Buffer.from("pullrequestadded"),
+      issueAccount.toBuffer(),
+      pullRequestCreator.publicKey.toBuffer(),
+    ]);
     await program.methods
-      .acceptIssueVote()
+      .addPr(constant.pullRequestMetadataUri)
       .accounts({
-        initiator: initiator.publicKey,
-        repositoryAccount: repositoryAccount,
+        pullRequestVerifiedUser: pullRequestCreatorVerifiedAccount,
         issue: issueAccount,
-        pullRequestMetadataAccount: pullRequestMetadataAccount,
+        pullRequestMetadataAccount,
+        pullRequestAddr: pullRequestCreator.publicKey,
         systemProgram: web3.SystemProgram.programId,
       })
-      .signers([initiator])
+      .signers([pullRequestCreator])
       .rpc(rpcConfig);
+
+    const votePr = (staker, issueStakerAccount) =>
+      program.methods
+        .votePr()
+        .accounts({
+          programConfig: global.programConfig,
+          issueAccount,
+          issueStaker: staker.publicKey,
+          issueStakerAccount,
+          pullRequestMetadataAccount,
+          repository: repositoryAccount,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([staker])
+        .rpc(rpcConfig);
+    const acceptIssueVote = () =>
+      program.methods
+        .acceptIssueVote()
+        .accounts({
+          initiator: repositoryCreator.publicKey,
+          repositoryAccount,
+          issue: issueAccount,
+          pullRequestMetadataAccount,
+          programConfig: global.programConfig,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([repositoryCreator])
+        .rpc(rpcConfig);
+
+    //the only vote cast is half of all voting power, which is not a majority
+    await votePr(repositoryCreator, stakerAccounts[0]);
+    await setVotingEnd(1);
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    try {
+      await acceptIssueVote();
+      assert.fail("merged with half of the voting power");
+    } catch (err) {
+      assert.equal(err.error.errorCode.code, "NotEnoughVotesForIssueMerge");
+    }
+
+    await setVotingEnd(72 * 60 * 60);
+    await votePr(secondStaker, stakerAccounts[1]);
+    await setVotingEnd(1);
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await acceptIssueVote();
+
+    const issue = await program.account.issue.fetch(issueAccount);
+    assert.isNotNull(issue.closedAt);
+    await setVotingEnd(72 * 60 * 60);
   });
 });
@@ -0,0 +1,120 @@
+//writes the accounts of a repository created before curves, vesting plans and
+//governance were configurable, loaded by the test validator for migrate_repository
+//usage: node tests/DefiOS/fixtures/legacy_repository.js
+const crypto = require("crypto");
+const fs = require("fs");
+const path = require("path");
+const { PublicKey } = require("@solana/web3.js");
+
+const PROGRAM_ID = new PublicKey("A4ka2TchTkFPcdtVctj7imhAtKvwapvihcR456aAW663");
+const TOKEN_PROGRAM_ID = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const REPOSITORY_ID = "legacy-repository";
+//legacy repositories minted VESTING_NUMBER * TOKEN_VEST_AMOUNT whole tokens
+const DECIMALS = 1;
+const VESTED_SUPPLY = 4n * 2500n * 10n ** BigInt(DECIMALS);
+const LEGACY_REPOSITORY_SPACE = 8 + 528;
+const MINT_SPACE = 82;
+
+const borshString = (value) => {
+  const length = Buffer.alloc(4);
+  length.writeUInt32LE(Buffer.byteLength(value));
+  return Buffer.concat([length, Buffer.from(value)]);
+};
+const u64 = (value) => {
+  const buffer = Buffer.alloc(8);
+  buffer.writeBigUInt64LE(BigInt(value));
+  return buffer;
+};
+const u32 = (value) => {
+  const buffer = Buffer.alloc(4);
+  buffer.writeUInt32LE(value);
+  return buffer;
+};
+
+const fixture = (pubkey, owner, data, space) => {
+  const padded = Buffer.alloc(space);
+  data.copy(padded);
+  return {
+    pubkey: pubkey.toBase58(),
+    account: {
+      lamports: (space + 128) * 3480 * 2,
+      data: [padded.toString("base64"), "base64"],
+      owner,
+      executable: false,
+      rentEpoch: 0,
+      space,
+    },
+  };
+};
+
+const repositoryCreator = new PublicKey(
+  crypto.createHash("sha256").update("legacy repository creator").digest()
+);
+const [repository, repositoryBump] = PublicKey.findProgramAddressSync(
+  [
+    Buffer.from("repository"),
+    Buffer.from(REPOSITORY_ID),
+    repositoryCreator.toBuffer(),
+  ],
+  PROGRAM_ID
+);
+const [rewardsMint] = PublicKey.findProgramAddressSync(
+  [Buffer.from("Miners"), Buffer.from("MinerC"), repository.toBuffer()],
+  PROGRAM_ID
+);
+const [vestingAccount] = PublicKey.findProgramAddressSync(
+  [Buffer.from("vesting"), repository.toBuffer()],
+  PROGRAM_ID
+);
+
+//Repository as laid out before curve_config and the fields after it existed
+const repositoryData = Buffer.concat([
+  crypto
+    .createHash("sha256")
+    .update("account:Repository")
+    .digest()
+    .subarray(0, 8),
+  Buffer.from([repositoryBump]),
+  u64(0),
+  repositoryCreator.toBuffer(),
+  borshString(REPOSITORY_ID),
+  borshString("repository created before curves were configurable"),
+  borshString("https://github.com/defi-os/legacy-repository"),
+  Buffer.from([1]),
+  vestingAccount.toBuffer(),
+  rewardsMint.toBuffer(),
+  Buffer.from([1, 0]),
+  u32(0),
+  u32(0),
+]);
+
+//the mint is its own authority, its whole supply is the legacy vesting carve-out
+const mintData = Buffer.concat([
+  u32(1),
+  rewardsMint.toBuffer(),
+  u64(VESTED_SUPPLY),
+  Buffer.from([DECIMALS, 1]),
+  u32(0),
+  Buffer.alloc(32),
+]);
+
+for (const [name, account] of [
+  [
+    "legacy_repository.json",
+    fixture(
+      repository,
+      PROGRAM_ID.toBase58(),
+      repositoryData,
+      LEGACY_REPOSITORY_SPACE
+    ),
+  ],
+  [
+    "legacy_rewards_mint.json",
+    fixture(rewardsMint, TOKEN_PROGRAM_ID, mintData, MINT_SPACE),
+  ],
+]) {
+  fs.writeFileSync(
+    path.join(__dirname, name),
+    JSON.stringify(account, null, 2) + "\n"
+  );
+}
@@ -162,6 +162,10 @@ async function create_spl_token(
   ];
 }
 
+async function delay(ms) {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
 export {
   create_keypair,
   create_name_router,
@@ -169,4 +173,5 @@ export {
   create_verified_user,
   get_pda_from_seeds,
   get_metadata_account,
+  delay,
 };
@@ -29,6 +29,28 @@ const repositoryUri = "https://github.com/sunguru98/defios";
 const objectiveId = "1";
 const proposalId = "1";
 const proposalUri = "https://github.com/sunguru98/defios";
+const tradeFeeBps = 100;
+const vestingPlan = {
+  mode: { tranche: {} },
+  totalAllocation: new BN(10000),
+  cliff: new BN(0),
+  numberOfTranches: 4,
+  releaseInterval: new BN(1000000),
+  linearDuration: new BN(0),
+};
+const linearVestingPlan = {
+  mode: { linear: {} },
+  totalAllocation: new BN(10000),
+  cliff: new BN(0),
+  numberOfTranches: 0,
+  releaseInterval: new BN(0),
+  linearDuration: new BN(1000000),
+};
+const curveConfig = {
+  curveType: { linear: {} },
+  slope: new BN(2),
+  basePrice: new BN(0),
+};
 
 export {
   signatureVersion,
@@ -54,4 +76,8 @@ export {
   objectiveId,
   proposalId,
   proposalUri,
+  curveConfig,
+  tradeFeeBps,
+  vestingPlan,
+  linearVestingPlan,
 };